@@ -0,0 +1,303 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    spanned::Spanned, FnArg, GenericArgument, Ident, ImplItem, ImplItemMethod, ItemImpl, Pat,
+    PathArguments, Type,
+};
+
+/// A system parameter required by one or more of the action methods.
+struct SystemParam {
+    ident: Ident,
+    ty: Type,
+    mutable: bool,
+}
+
+/// How an argument of an action method should be supplied by the generated system.
+enum MethodArg {
+    Action { mutable: bool },
+    Param { ident: Ident, mutable: bool },
+}
+
+struct ActionMethod {
+    ident: Ident,
+    receiver_mutable: bool,
+    args: Vec<MethodArg>,
+}
+
+pub fn expand_goap_action(input: ItemImpl) -> syn::Result<TokenStream> {
+    if input.trait_.is_some() {
+        return Err(syn::Error::new_spanned(
+            &input,
+            "`goap_action` must be used on an inherent `impl` block",
+        ));
+    }
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`goap_action` does not support generic `impl` blocks",
+        ));
+    }
+
+    let mut params: Vec<SystemParam> = vec![];
+    let mut evaluate = None;
+    let mut start = None;
+    let mut execute = None;
+
+    for item in input.items.iter() {
+        let ImplItem::Method(method) = item else {
+            continue;
+        };
+
+        let slot = match method.sig.ident.to_string().as_str() {
+            "evaluate" => &mut evaluate,
+            "start" => &mut start,
+            "execute" => &mut execute,
+            _ => continue,
+        };
+
+        *slot = Some(parse_method(method, &mut params)?);
+    }
+
+    let Some(execute) = execute else {
+        return Err(syn::Error::new_spanned(
+            &input.self_ty,
+            "`goap_action` requires an `execute` method",
+        ));
+    };
+
+    let self_ty = &input.self_ty;
+
+    let evaluate_arm = match evaluate {
+        Some(method) => {
+            let call = method_call(&method);
+            quote! {
+                *action_state = ::bevy_goap::ActionState::EvaluationComplete(#call);
+            }
+        }
+        None => quote! {
+            *action_state = ::bevy_goap::ActionState::EvaluationComplete(
+                ::bevy_goap::EvaluationResult::Success,
+            );
+        },
+    };
+
+    let start_arm = match start {
+        Some(method) => {
+            let call = method_call(&method);
+            quote! {
+                *action_state = #call;
+            }
+        }
+        None => quote! {
+            *action_state = ::bevy_goap::ActionState::Executing;
+        },
+    };
+
+    let execute_call = method_call(&execute);
+
+    let param_decls = params.iter().map(|param| {
+        let SystemParam { ident, ty, mutable } = param;
+        let mutability = mutable.then(|| quote!(mut));
+        quote!(#mutability #ident: #ty)
+    });
+
+    Ok(quote! {
+        #input
+
+        impl #self_ty {
+            /// Drives the `ActionState` of every `Action` with this marker component. Add this
+            /// system to `GoapStage::Actions`.
+            #[allow(unused_mut, clippy::too_many_arguments)]
+            pub fn action_system(
+                mut __goap_action_query: ::bevy_goap::__private::Query<(
+                    &mut #self_ty,
+                    &mut ::bevy_goap::Action,
+                    &mut ::bevy_goap::ActionState,
+                )>,
+                #(#param_decls),*
+            ) {
+                for (mut marker, mut action, mut action_state) in __goap_action_query.iter_mut() {
+                    match *action_state {
+                        ::bevy_goap::ActionState::Evaluate => {
+                            #evaluate_arm
+                        }
+                        ::bevy_goap::ActionState::NotInPlan(_) => {
+                            *action_state = ::bevy_goap::ActionState::Idle;
+                        }
+                        ::bevy_goap::ActionState::Started => {
+                            #start_arm
+                        }
+                        ::bevy_goap::ActionState::Executing => {
+                            let next_action_state = #execute_call;
+
+                            // Avoid triggering change detection when the action is still executing.
+                            if next_action_state != *action_state {
+                                *action_state = next_action_state;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn parse_method(
+    method: &ImplItemMethod,
+    params: &mut Vec<SystemParam>,
+) -> syn::Result<ActionMethod> {
+    let mut inputs = method.sig.inputs.iter();
+
+    let receiver_mutable = match inputs.next() {
+        Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() => {
+            receiver.mutability.is_some()
+        }
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &method.sig,
+                "expected the first argument to be `&self` or `&mut self`",
+            ))
+        }
+    };
+
+    let mut args = vec![];
+
+    for input in inputs {
+        let FnArg::Typed(pat_type) = input else {
+            unreachable!("only the first argument can be a receiver");
+        };
+
+        let Type::Reference(reference) = pat_type.ty.as_ref() else {
+            return Err(syn::Error::new_spanned(
+                &pat_type.ty,
+                "expected a reference to the `Action` or to a system parameter",
+            ));
+        };
+
+        let mutable = reference.mutability.is_some();
+
+        if is_action_type(&reference.elem) {
+            args.push(MethodArg::Action { mutable });
+            continue;
+        }
+
+        let ident = match pat_type.pat.as_ref() {
+            Pat::Ident(pat_ident) => pat_ident.ident.clone(),
+            pat => {
+                return Err(syn::Error::new_spanned(
+                    pat,
+                    "expected a named system parameter",
+                ))
+            }
+        };
+
+        // Prefix the system parameters so they cannot clash with the names used inside the
+        // generated system.
+        let param_ident = format_ident!("param_{}", ident);
+
+        match params.iter_mut().find(|param| param.ident == param_ident) {
+            Some(param) => {
+                if !same_type(&param.ty, &reference.elem) {
+                    return Err(syn::Error::new(
+                        pat_type.ty.span(),
+                        format!("system parameter `{ident}` is declared with different types"),
+                    ));
+                }
+
+                param.mutable |= mutable;
+            }
+            None => params.push(SystemParam {
+                ident: param_ident.clone(),
+                ty: reference.elem.as_ref().clone(),
+                mutable,
+            }),
+        }
+
+        args.push(MethodArg::Param {
+            ident: param_ident,
+            mutable,
+        });
+    }
+
+    Ok(ActionMethod {
+        ident: method.sig.ident.clone(),
+        receiver_mutable,
+        args,
+    })
+}
+
+fn method_call(method: &ActionMethod) -> TokenStream {
+    let ident = &method.ident;
+
+    let receiver = if method.receiver_mutable {
+        quote!(&mut *marker)
+    } else {
+        quote!(&*marker)
+    };
+
+    let args = method.args.iter().map(|arg| match arg {
+        MethodArg::Action { mutable: true } => quote!(&mut *action),
+        MethodArg::Action { mutable: false } => quote!(&*action),
+        MethodArg::Param {
+            ident,
+            mutable: true,
+        } => quote!(&mut #ident),
+        MethodArg::Param {
+            ident,
+            mutable: false,
+        } => quote!(&#ident),
+    });
+
+    quote!(Self::#ident(#receiver, #(#args),*))
+}
+
+fn is_action_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Action"),
+        _ => false,
+    }
+}
+
+/// Whether two system parameter types are the same. Paths are compared by their last segment,
+/// so that `Res<Time>` and `bevy::prelude::Res<Time>` are the same type.
+fn same_type(a: &Type, b: &Type) -> bool {
+    match (a, b) {
+        (Type::Paren(a), b) => same_type(&a.elem, b),
+        (a, Type::Paren(b)) => same_type(a, &b.elem),
+        (Type::Path(a), Type::Path(b)) if a.qself.is_none() && b.qself.is_none() => {
+            match (a.path.segments.last(), b.path.segments.last()) {
+                (Some(a), Some(b)) => {
+                    a.ident == b.ident && same_arguments(&a.arguments, &b.arguments)
+                }
+                _ => false,
+            }
+        }
+        (Type::Reference(a), Type::Reference(b)) => {
+            a.mutability.is_some() == b.mutability.is_some() && same_type(&a.elem, &b.elem)
+        }
+        (Type::Tuple(a), Type::Tuple(b)) => {
+            a.elems.len() == b.elems.len()
+                && a.elems.iter().zip(&b.elems).all(|(a, b)| same_type(a, b))
+        }
+        (a, b) => a == b,
+    }
+}
+
+fn same_arguments(a: &PathArguments, b: &PathArguments) -> bool {
+    match (a, b) {
+        (PathArguments::AngleBracketed(a), PathArguments::AngleBracketed(b)) => {
+            a.args.len() == b.args.len()
+                && a.args.iter().zip(&b.args).all(|(a, b)| match (a, b) {
+                    (GenericArgument::Type(a), GenericArgument::Type(b)) => same_type(a, b),
+                    (a, b) => a == b,
+                })
+        }
+        (a, b) => a == b,
+    }
+}
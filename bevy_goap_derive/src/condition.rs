@@ -0,0 +1,76 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    DeriveInput, Ident, LitStr, Token, Type,
+};
+
+enum ConditionArg {
+    Name(LitStr),
+    Value(Box<Type>),
+}
+
+impl Parse for ConditionArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: Ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+
+        if key == "name" {
+            Ok(ConditionArg::Name(input.parse()?))
+        } else if key == "value" {
+            Ok(ConditionArg::Value(input.parse()?))
+        } else {
+            Err(syn::Error::new_spanned(
+                key,
+                "unknown `condition` argument, expected `name` or `value`",
+            ))
+        }
+    }
+}
+
+pub fn derive_condition(input: DeriveInput) -> syn::Result<TokenStream> {
+    let mut name = None;
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("condition"))
+    {
+        let args = attr.parse_args_with(Punctuated::<ConditionArg, Token![,]>::parse_terminated)?;
+
+        for arg in args {
+            match arg {
+                ConditionArg::Name(lit) => name = Some(lit),
+                ConditionArg::Value(ty) => check_value_type(&ty)?,
+            }
+        }
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let name_fn = name.map(|name| {
+        quote! {
+            fn name() -> &'static str {
+                #name
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::bevy_goap::Condition for #ident #ty_generics #where_clause {
+            #name_fn
+        }
+    })
+}
+
+fn check_value_type(ty: &Type) -> syn::Result<()> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() && path.path.is_ident("bool") => Ok(()),
+        _ => Err(syn::Error::new_spanned(
+            ty,
+            "`Condition`s currently only support `bool` values",
+        )),
+    }
+}
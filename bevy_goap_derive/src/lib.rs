@@ -0,0 +1,68 @@
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, ItemImpl};
+
+mod action;
+mod condition;
+
+/// Implements `bevy_goap::Condition` for a type.
+///
+/// The name and value type of the condition can optionally be set with the `condition` attribute:
+///
+/// ```ignore
+/// #[derive(Condition)]
+/// #[condition(name = "HasAxe", value = bool)]
+/// struct HasAxeCondition;
+/// ```
+///
+/// If no name is given, the full type name is used.
+#[proc_macro_derive(Condition, attributes(condition))]
+pub fn derive_condition(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    condition::derive_condition(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates an `action_system` associated function for an `Action` marker component from the
+/// `evaluate`, `start` and `execute` methods of an `impl` block.
+///
+/// The generated system drives the `ActionState` of every `Action` with this marker component:
+/// - `ActionState::Evaluate` calls `evaluate`, which returns the `EvaluationResult`. If omitted,
+///   the `Action` always evaluates successfully.
+/// - `ActionState::NotInPlan` transitions back to `ActionState::Idle`.
+/// - `ActionState::Started` calls `start`, which returns the next `ActionState`. If omitted, the
+///   `Action` transitions straight to `ActionState::Executing`.
+/// - `ActionState::Executing` calls `execute`, which returns the next `ActionState`.
+///
+/// Each method takes `self` by reference, optionally followed by a `&Action` or `&mut Action`,
+/// followed by references to any system parameters it requires. System parameters are shared
+/// between the methods by argument name.
+///
+/// ```ignore
+/// #[goap_action]
+/// impl CollectWoodAction {
+///     fn execute(&mut self, action: &Action, commands: &mut Commands) -> ActionState {
+///         ActionState::Complete
+///     }
+/// }
+///
+/// app.add_goap_system(GoapStage::Actions, CollectWoodAction::action_system);
+/// ```
+#[proc_macro_attribute]
+pub fn goap_action(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`goap_action` does not take any arguments",
+        )
+        .into_compile_error()
+        .into();
+    }
+
+    let input = parse_macro_input!(item as ItemImpl);
+
+    action::expand_goap_action(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
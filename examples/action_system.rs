@@ -0,0 +1,60 @@
+//! Drives an `Action` with a hand-written system that matches on its `ActionState`, rather than
+//! with the `GoapAction` trait used by the lumberjack example.
+
+use bevy::prelude::*;
+use bevy_goap::{
+    Action, ActionState, Actor, ActorState, Condition, EvaluationResult, GoapAppExt, GoapPlugin,
+    GoapStage,
+};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Component, Clone)]
+struct CollectWoodAction;
+
+#[derive(Condition)]
+struct HasWoodCondition;
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default())
+        .add_goap_system(GoapStage::Actions, collect_wood_action_system);
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_goal(HasWoodCondition, true)
+        .with_action(
+            Action::build(CollectWoodAction)
+                .with_precondition(HasWoodCondition, false)
+                .with_postcondition(HasWoodCondition, true),
+        );
+    let actor_entity = app.world.spawn(lumberjack).id();
+
+    for _ in 0..5 {
+        app.update();
+        println!("{:?}", app.world.get::<ActorState>(actor_entity).unwrap());
+    }
+}
+
+fn collect_wood_action_system(mut query: Query<&mut ActionState, With<CollectWoodAction>>) {
+    for mut action_state in query.iter_mut() {
+        match *action_state {
+            ActionState::Evaluate => {
+                *action_state = ActionState::EvaluationComplete(EvaluationResult::Success);
+            }
+            ActionState::NotInPlan(_) => {
+                *action_state = ActionState::Idle;
+            }
+            ActionState::Started => {
+                println!("Starting to collect wood!");
+                *action_state = ActionState::Executing;
+            }
+            ActionState::Executing => {
+                println!("Collecting wood!");
+                *action_state = ActionState::Complete;
+            }
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Drives an `Action` with the system generated by the `goap_action` attribute, rather than with
+//! the `GoapAction` trait used by the lumberjack example.
+
+use bevy::prelude::*;
+use bevy_goap::{
+    goap_action, Action, ActionState, Actor, ActorState, Condition, GoapAppExt, GoapPlugin,
+    GoapStage,
+};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Component, Clone)]
+struct CollectWoodAction;
+
+#[goap_action]
+impl CollectWoodAction {
+    fn start(&mut self, _action: &Action) -> ActionState {
+        println!("Starting to collect wood!");
+        ActionState::Executing
+    }
+
+    fn execute(&mut self, _action: &Action) -> ActionState {
+        println!("Collecting wood!");
+        ActionState::Complete
+    }
+}
+
+#[derive(Condition)]
+struct HasWoodCondition;
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default())
+        .add_goap_system(GoapStage::Actions, CollectWoodAction::action_system);
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_goal(HasWoodCondition, true)
+        .with_action(
+            Action::build(CollectWoodAction)
+                .with_precondition(HasWoodCondition, false)
+                .with_postcondition(HasWoodCondition, true),
+        );
+    let actor_entity = app.world.spawn(lumberjack).id();
+
+    for _ in 0..5 {
+        app.update();
+        println!("{:?}", app.world.get::<ActorState>(actor_entity).unwrap());
+    }
+}
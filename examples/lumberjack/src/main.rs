@@ -1,6 +1,13 @@
-use bevy::prelude::*;
+use bevy::{
+    ecs::system::{
+        lifetimeless::{Read, SCommands, SQuery, Write},
+        SystemParamItem,
+    },
+    prelude::*,
+};
 use bevy_goap::{
-    Action, ActionState, Actor, ActorState, Condition, EvaluationResult, GoapPlugin, GoapStage,
+    Action, ActionProgress, Actor, ActorState, Blackboard, BlackboardKey, Condition,
+    EvaluationResult, GoapAction, GoapAppExt, GoapLogLevel, GoapLogSettings, GoapPlugin, GoapStage,
 };
 use environment::*;
 use navigation::{navigation_system, Navigation};
@@ -12,22 +19,18 @@ fn main() {
     let mut app = App::new();
 
     app.add_plugins(DefaultPlugins)
-        .add_plugin(GoapPlugin)
+        .add_plugin(GoapPlugin {
+            log: GoapLogSettings::all(GoapLogLevel::Info),
+            ..default()
+        })
         .add_startup_system(setup)
         .add_startup_system(create_lumberjack)
         .add_startup_system(create_axes_system)
         .add_startup_system(create_trees_system)
-        .add_system_set_to_stage(
-            GoapStage::Actions,
-            SystemSet::new()
-                .with_system(get_axe_action_system)
-                .with_system(chop_tree_action_system)
-                .with_system(collect_wood_action_system),
-        )
-        .add_system_set_to_stage(
-            GoapStage::Actors,
-            SystemSet::new().with_system(lumberjack_actor_system),
-        )
+        .add_goap_action::<GetAxeAction>()
+        .add_goap_action::<ChopTreeAction>()
+        .add_goap_action::<CollectWoodAction>()
+        .add_goap_system(GoapStage::Actors, lumberjack_actor_system)
         .add_system(navigation_system);
 
     #[cfg(feature = "inspector")]
@@ -58,7 +61,7 @@ fn create_lumberjack(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
-    let get_axe_action = Action::build(GetAxeAction::default())
+    let get_axe_action = Action::build(GetAxeAction)
         .with_precondition(ActorHasAxeCondition, false)
         .with_postcondition(ActorHasAxeCondition, true);
 
@@ -114,83 +117,105 @@ fn lumberjack_actor_system(
     }
 }
 
-#[derive(Default, Component, Clone)]
-struct GetAxeAction {
-    axe_entity: Option<Entity>,
+/// The axe the lumberjack is walking to.
+struct TargetAxe;
+
+impl BlackboardKey for TargetAxe {
+    type Value = Entity;
 }
 
-fn get_axe_action_system(
-    mut commands: Commands,
-    mut query: Query<(&mut GetAxeAction, &Action, &mut ActionState), With<GetAxeAction>>,
-    actor_transforms_query: Query<&Transform, With<Lumberjack>>,
-    axes: Query<(Entity, &Transform), With<Axe>>,
-    navigations: Query<&Navigation>,
-) {
-    for (mut get_axe_action, action, mut action_state) in query.iter_mut() {
-        match *action_state {
-            ActionState::Evaluate => {
-                println!("Evaluating GetAxeAction");
-
-                // Is there an axe available?
-                if axes.iter().count() > 0 {
-                    *action_state = ActionState::EvaluationComplete(EvaluationResult::Success);
-                } else {
-                    *action_state = ActionState::EvaluationComplete(EvaluationResult::Failure);
-                }
-            }
-            ActionState::NotInPlan(_) => {
-                *action_state = ActionState::Idle;
-            }
-            ActionState::Started => {
-                println!("Starting GetAxeAction");
-
-                let actor_pos = actor_transforms_query
-                    .get(action.actor_entity)
-                    .unwrap()
-                    .translation;
-
-                let closest_axe = axes.iter().min_by_key(|(_, axe_transform)| {
-                    (axe_transform.translation - actor_pos).length() as i32
-                });
-
-                if let Some((axe_entity, _)) = closest_axe {
-                    get_axe_action.axe_entity = Some(axe_entity);
-
-                    commands.entity(action.actor_entity).insert(Navigation {
-                        navigator: action.actor_entity,
-                        target: axe_entity,
-                        speed: 15.,
-                        is_done: false,
-                        is_err: false,
-                    });
-
-                    *action_state = ActionState::Executing;
-                } else {
-                    *action_state = ActionState::Failure;
-                }
-            }
-            ActionState::Executing => {
-                println!("Getting axe!");
-
-                let nav = navigations.get(action.actor_entity).unwrap();
-
-                if nav.is_err {
-                    *action_state = ActionState::Failure;
-                } else if nav.is_done {
-                    commands
-                        .entity(get_axe_action.axe_entity.unwrap())
-                        .despawn_recursive();
-
-                    *action_state = ActionState::Complete;
-                }
-            }
-            _ => {}
-        };
+#[derive(Component, Clone)]
+struct GetAxeAction;
+
+impl GoapAction for GetAxeAction {
+    type Param = (
+        SCommands,
+        SQuery<Read<Transform>, With<Lumberjack>>,
+        SQuery<(Entity, Read<Transform>), With<Axe>>,
+        SQuery<Read<Navigation>>,
+        SQuery<Write<Blackboard>>,
+    );
+
+    fn evaluate(
+        &mut self,
+        _action: &mut Action,
+        (_, _, axes, _, _): &mut SystemParamItem<Self::Param>,
+    ) -> EvaluationResult {
+        println!("Evaluating GetAxeAction");
+
+        // Is there an axe available?
+        if axes.iter().count() > 0 {
+            EvaluationResult::Success
+        } else {
+            EvaluationResult::Failure
+        }
+    }
+
+    fn on_start(
+        &mut self,
+        action: &Action,
+        (commands, actor_transforms_query, axes, _, blackboards): &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        println!("Starting GetAxeAction");
+
+        let actor_pos = actor_transforms_query
+            .get(action.actor_entity)
+            .unwrap()
+            .translation;
+
+        let closest_axe = axes.iter().min_by_key(|(_, axe_transform)| {
+            (axe_transform.translation - actor_pos).length() as i32
+        });
+
+        if let Some((axe_entity, _)) = closest_axe {
+            blackboards
+                .get_mut(action.actor_entity)
+                .unwrap()
+                .insert(TargetAxe, axe_entity);
+
+            commands.entity(action.actor_entity).insert(Navigation {
+                navigator: action.actor_entity,
+                target: axe_entity,
+                speed: 15.,
+                is_done: false,
+                is_err: false,
+            });
+
+            ActionProgress::Running
+        } else {
+            ActionProgress::Failure
+        }
+    }
+
+    fn tick(
+        &mut self,
+        action: &Action,
+        (commands, _, _, navigations, blackboards): &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        println!("Getting axe!");
+
+        let nav = navigations.get(action.actor_entity).unwrap();
+
+        if nav.is_err {
+            ActionProgress::Failure
+        } else if nav.is_done {
+            let axe_entity = blackboards
+                .get_mut(action.actor_entity)
+                .unwrap()
+                .remove(TargetAxe)
+                .unwrap();
+
+            commands.entity(axe_entity).despawn_recursive();
+
+            ActionProgress::Complete
+        } else {
+            ActionProgress::Running
+        }
     }
 }
 
+#[derive(Condition)]
 struct ActorHasAxeCondition;
-impl Condition for ActorHasAxeCondition {}
 
 #[derive(Component, Clone)]
 struct ChopTreeAction {
@@ -209,107 +234,124 @@ impl ChopTreeAction {
     }
 }
 
-fn chop_tree_action_system(
-    mut commands: Commands,
-    mut query: Query<(&mut ActionState, &mut ChopTreeAction, &Action)>,
-    actor_transforms_query: Query<&Transform, With<Lumberjack>>,
-    trees: Query<(Entity, &Transform), With<Tree>>,
-    navigations: Query<&Navigation>,
-) {
-    for (mut action_state, mut chop_tree_action, action) in query.iter_mut() {
-        match *action_state {
-            ActionState::Evaluate => {
-                println!("Evaluating ChopTreeAction");
-
-                // Is there a tree available?
-                if trees.iter().count() > 0 {
-                    *action_state = ActionState::EvaluationComplete(EvaluationResult::Success);
-                } else {
-                    *action_state = ActionState::EvaluationComplete(EvaluationResult::Failure);
-                }
-            }
-            ActionState::NotInPlan(_) => {
-                *action_state = ActionState::Idle;
-            }
-            ActionState::Started => {
-                println!("Starting ChopTreeAction");
-
-                let actor_pos = actor_transforms_query
-                    .get(action.actor_entity)
-                    .unwrap()
-                    .translation;
-
-                let closest_tree = trees.iter().min_by_key(|(_, tree_transform)| {
-                    (tree_transform.translation - actor_pos).length() as i32
-                });
-
-                if let Some((tree_entity, _)) = closest_tree {
-                    chop_tree_action.tree_entity = Some(tree_entity);
-
-                    commands.entity(action.actor_entity).insert(Navigation {
-                        navigator: action.actor_entity,
-                        target: tree_entity,
-                        speed: 15.,
-                        is_done: false,
-                        is_err: false,
-                    });
-
-                    *action_state = ActionState::Executing;
-                } else {
-                    *action_state = ActionState::Failure;
-                }
-            }
-            ActionState::Executing => {
-                let nav = navigations.get(action.actor_entity).unwrap();
-
-                if nav.is_err {
-                    *action_state = ActionState::Failure;
-                } else if nav.is_done {
-                    chop_tree_action.current_chops += 1;
-                    println!("Chopped tree {} times!", chop_tree_action.current_chops);
-
-                    if chop_tree_action.current_chops >= chop_tree_action.max_chops {
-                        commands
-                            .entity(chop_tree_action.tree_entity.unwrap())
-                            .despawn_recursive();
-
-                        chop_tree_action.current_chops = 0;
-
-                        *action_state = ActionState::Complete;
-                    }
-                } else {
-                    println!("Moving to tree!");
-                }
-            }
-            _ => {}
+impl GoapAction for ChopTreeAction {
+    type Param = (
+        SCommands,
+        SQuery<Read<Transform>, With<Lumberjack>>,
+        SQuery<(Entity, Read<Transform>), With<Tree>>,
+        SQuery<Read<Navigation>>,
+    );
+
+    fn evaluate(
+        &mut self,
+        _action: &mut Action,
+        (_, _, trees, _): &mut SystemParamItem<Self::Param>,
+    ) -> EvaluationResult {
+        println!("Evaluating ChopTreeAction");
+
+        // Is there a tree available?
+        if trees.iter().count() > 0 {
+            EvaluationResult::Success
+        } else {
+            EvaluationResult::Failure
         }
     }
+
+    fn on_start(
+        &mut self,
+        action: &Action,
+        (commands, actor_transforms_query, trees, _): &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        println!("Starting ChopTreeAction");
+
+        let actor_pos = actor_transforms_query
+            .get(action.actor_entity)
+            .unwrap()
+            .translation;
+
+        let closest_tree = trees.iter().min_by_key(|(_, tree_transform)| {
+            (tree_transform.translation - actor_pos).length() as i32
+        });
+
+        if let Some((tree_entity, _)) = closest_tree {
+            self.tree_entity = Some(tree_entity);
+
+            commands.entity(action.actor_entity).insert(Navigation {
+                navigator: action.actor_entity,
+                target: tree_entity,
+                speed: 15.,
+                is_done: false,
+                is_err: false,
+            });
+
+            ActionProgress::Running
+        } else {
+            ActionProgress::Failure
+        }
+    }
+
+    fn tick(
+        &mut self,
+        action: &Action,
+        (commands, _, _, navigations): &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        let nav = navigations.get(action.actor_entity).unwrap();
+
+        if nav.is_err {
+            return ActionProgress::Failure;
+        }
+
+        if !nav.is_done {
+            println!("Moving to tree!");
+            return ActionProgress::Running;
+        }
+
+        self.current_chops += 1;
+        println!("Chopped tree {} times!", self.current_chops);
+
+        if self.current_chops < self.max_chops {
+            return ActionProgress::Running;
+        }
+
+        commands
+            .entity(self.tree_entity.unwrap())
+            .despawn_recursive();
+
+        self.current_chops = 0;
+
+        ActionProgress::Complete
+    }
+
+    fn on_cancel(&mut self, _action: &Action, _param: &mut SystemParamItem<Self::Param>) {
+        self.current_chops = 0;
+    }
 }
 
 #[derive(Component, Clone)]
 struct CollectWoodAction;
 
-fn collect_wood_action_system(mut query: Query<&mut ActionState, With<CollectWoodAction>>) {
-    for mut action_state in query.iter_mut() {
-        match *action_state {
-            ActionState::Evaluate => {
-                *action_state = ActionState::EvaluationComplete(EvaluationResult::Success);
-            }
-            ActionState::NotInPlan(_) => {
-                *action_state = ActionState::Idle;
-            }
-            ActionState::Started => {
-                println!("Starting to collect wood!");
-                *action_state = ActionState::Executing;
-            }
-            ActionState::Executing => {
-                println!("Collecting wood!");
-                *action_state = ActionState::Complete;
-            }
-            _ => (),
-        }
+impl GoapAction for CollectWoodAction {
+    type Param = ();
+
+    fn on_start(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        println!("Starting to collect wood!");
+        ActionProgress::Running
+    }
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        println!("Collecting wood!");
+        ActionProgress::Complete
     }
 }
 
+#[derive(Condition)]
+#[condition(name = "ActorHasWood", value = bool)]
 struct ActorHasWoodCondition;
-impl Condition for ActorHasWoodCondition {}
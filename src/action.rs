@@ -1,17 +1,46 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use bevy::prelude::{Changed, Commands, Component, Entity, ParamSet, Query};
+use bevy::{
+    ecs::{
+        entity::{EntityMap, MapEntities, MapEntitiesError},
+        query::WorldQuery,
+        reflect::ReflectMapEntities,
+    },
+    log::{debug, info, warn},
+    prelude::{
+        Changed, Commands, Component, DespawnRecursiveExt, Entity, EventReader, EventWriter,
+        FromReflect, FromWorld, Local, ParamSet, Query, Reflect, ReflectComponent, Res, Time,
+        World,
+    },
+    utils::{HashMap, HashSet},
+};
+
+#[cfg(feature = "serde")]
+use bevy::reflect::{ReflectDeserialize, ReflectSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use crate::{
     actor::{Actor, ActorState},
-    common::MarkerComponent,
+    common::{InternedName, MarkerComponent},
+    compound::cancel_sub_plans,
     condition::Condition,
-    state::GoapState,
+    events::{
+        ActionCompleted, ActionFailed, ActionStarted, ActionTimedOut, LifecycleEvents,
+        PlanCompleted, PlanFailed, PlanFailureReason,
+    },
+    history::{PlanHistory, PlanOutcome},
+    planner,
+    settings::{GoapLogLevel, GoapLogSettings, TimeoutSettings},
+    state::{ConditionId, GoapState},
 };
 
-#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Component, Reflect, FromReflect, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[reflect(Component, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ActionState {
     /// An `Action` in this state is currently not executing, nor being considered for a plan.
+    #[default]
     Idle,
     /// A plan has been requested for the `Actor` owning this `Action`, so this `Action` must be be evaluated for its viability to be included in the plan, and, if viable, to update the `Action` cost.
     ///
@@ -50,14 +79,83 @@ pub enum ActionState {
 }
 
 impl ActionState {
+    /// Whether user code may transition an `Action` from this state to the `next` state.
+    fn can_transition_to(&self, next: &ActionState) -> bool {
+        matches!(
+            (self, next),
+            (
+                ActionState::Evaluate,
+                ActionState::EvaluationComplete(
+                    EvaluationResult::Success | EvaluationResult::Failure
+                )
+            ) | (ActionState::NotInPlan(_), ActionState::Idle)
+                | (
+                    ActionState::Started,
+                    ActionState::Executing | ActionState::Complete | ActionState::Failure
+                )
+                | (
+                    ActionState::Executing,
+                    ActionState::Complete | ActionState::Failure
+                )
+        )
+    }
+}
+
+/// The last `ActionState` of an `Action` that was either issued by the crate, or reached through a
+/// valid transition by user code.
+#[derive(Component, Reflect, Clone, Copy, Debug, Default)]
+#[reflect(Component)]
+pub(crate) struct LastValidActionState(pub(crate) ActionState);
+
+/// Mutable access to an `ActionState`, for transitions issued by the crate.
+#[derive(WorldQuery)]
+#[world_query(mutable)]
+pub(crate) struct ActionStateQuery {
+    state: &'static mut ActionState,
+    last_valid: &'static mut LastValidActionState,
+}
+
+impl ActionStateQueryItem<'_> {
+    pub(crate) fn get(&self) -> ActionState {
+        *self.state
+    }
+
+    pub(crate) fn issue(&mut self, next: ActionState) {
+        *self.state = next;
+        self.last_valid.0 = next;
+    }
+
     pub(crate) fn mark_not_in_plan(&mut self) {
-        if let ActionState::EvaluationComplete(result) = self {
-            *self = ActionState::NotInPlan(!matches!(result, EvaluationResult::Skipped));
+        if let ActionState::EvaluationComplete(result) = *self.state {
+            self.issue(ActionState::NotInPlan(!matches!(
+                result,
+                EvaluationResult::Skipped
+            )));
         }
     }
 }
 
-#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+impl ActionStateQueryReadOnlyItem<'_> {
+    pub(crate) fn get(&self) -> ActionState {
+        *self.state
+    }
+}
+
+/// Sent when user code makes an invalid transition of an `ActionState`, e.g. from
+/// `ActionState::Idle` straight to `ActionState::Complete`.
+///
+/// The invalid transition is reverted, so the `ActionState` remains in the `from` state.
+#[derive(Debug, Clone)]
+pub struct InvalidActionTransition {
+    pub action_entity: Entity,
+    pub actor_entity: Entity,
+    pub from: ActionState,
+    pub to: ActionState,
+}
+
+#[derive(Component, Reflect, FromReflect, Clone, Copy, PartialEq, Eq, Debug)]
+#[reflect(PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum EvaluationResult {
     /// The `Action` has been evaluated and is deemed to be viable as a candidate in the next plan, with an updated cost.
     Success,
@@ -67,33 +165,145 @@ pub enum EvaluationResult {
     Skipped,
 }
 
-#[derive(Component, Clone, Debug)]
+/// Reflected as an opaque value, since its conditions are keyed by `Condition` names. Scenes
+/// containing `Action`s can only be serialized with the `serde` feature.
+#[derive(Component, Reflect, Clone, Debug)]
+#[cfg_attr(not(feature = "serde"), reflect_value(Component, MapEntities))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    reflect_value(Component, MapEntities, Serialize, Deserialize)
+)]
 pub struct Action {
     pub actor_entity: Entity,
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::common::deserialize_name")
+    )]
+    pub(crate) name: InternedName,
     pub(crate) preconditions: GoapState,
     pub(crate) postconditions: GoapState,
     pub(crate) cost: i32,
+    pub(crate) timeout: Option<Duration>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "crate::common::deserialize_names")
+    )]
+    pub(crate) channels: Vec<InternedName>,
+}
+
+impl FromWorld for Action {
+    /// A placeholder used when inserting an `Action` through reflection, e.g. from a scene, which
+    /// is then overwritten by the reflected value. Its `actor_entity` is
+    /// `Action::PLACEHOLDER_ACTOR`.
+    fn from_world(_world: &mut World) -> Self {
+        Self {
+            actor_entity: Action::PLACEHOLDER_ACTOR,
+            name: "",
+            preconditions: GoapState::new(),
+            postconditions: GoapState::new(),
+            cost: 1,
+            timeout: None,
+            channels: vec![],
+        }
+    }
+}
+
+impl MapEntities for Action {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        self.actor_entity = entity_map.get(self.actor_entity)?;
+        Ok(())
+    }
 }
 
 impl Action {
+    /// The `actor_entity` of a placeholder `Action` created by `FromWorld`, which has not been
+    /// overwritten by a reflected value yet. The crate ignores `Action`s with this `actor_entity`.
+    pub const PLACEHOLDER_ACTOR: Entity = Entity::from_raw(u32::MAX);
+
+    /// Whether this is a placeholder `Action` created by `FromWorld`, rather than an `Actor`'s.
+    pub fn is_placeholder(&self) -> bool {
+        self.actor_entity == Self::PLACEHOLDER_ACTOR
+    }
+
+    /// This `Action` on `action_entity` as seen by the planner, with its current cost.
+    pub(crate) fn planner_action(
+        &self,
+        action_entity: Entity,
+    ) -> planner::Action<ConditionId, Entity> {
+        planner::Action {
+            id: action_entity,
+            preconditions: self.preconditions.state.clone(),
+            effects: self.postconditions.state.clone(),
+            cost: self.cost,
+        }
+    }
+
     pub fn build(marker_component: impl MarkerComponent + 'static) -> ActionBuilder {
         ActionBuilder {
             marker_component: Arc::new(marker_component),
             preconditions: GoapState::new(),
             postconditions: GoapState::new(),
+            cost: 1,
+            timeout: None,
+            channels: vec![],
         }
     }
 
+    /// The type name of the marker component of this `Action`.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn cost(&self) -> u32 {
+        self.cost as u32
+    }
+
     pub fn update_cost(&mut self, new_cost: u32) {
         self.cost = new_cost as i32;
     }
+
+    /// The resource channels this `Action` occupies while executing, see
+    /// `ActionBuilder::with_channel`.
+    pub fn channels(&self) -> &[&'static str] {
+        &self.channels
+    }
+
+    /// Whether this `Action` can execute alongside `other` in either order: both occupy channels,
+    /// none of which they share, and neither writes a `Condition` the other reads or writes.
+    pub(crate) fn can_execute_alongside(&self, other: &Action) -> bool {
+        let writes =
+            |action: &Action, condition| action.postconditions.state.get(condition).is_some();
+        let reads =
+            |action: &Action, condition| action.preconditions.state.get(condition).is_some();
+
+        !self.channels.is_empty()
+            && !other.channels.is_empty()
+            && self
+                .channels
+                .iter()
+                .all(|channel| !other.channels.contains(channel))
+            && self
+                .preconditions
+                .state
+                .iter()
+                .all(|(condition, _)| !writes(other, condition))
+            && self
+                .postconditions
+                .state
+                .iter()
+                .all(|(condition, _)| !reads(other, condition) && !writes(other, condition))
+    }
 }
 
 #[derive(Clone)]
 pub struct ActionBuilder {
-    marker_component: Arc<dyn MarkerComponent>,
-    preconditions: GoapState,
-    postconditions: GoapState,
+    pub(crate) marker_component: Arc<dyn MarkerComponent>,
+    pub(crate) preconditions: GoapState,
+    pub(crate) postconditions: GoapState,
+    pub(crate) cost: i32,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) channels: Vec<&'static str>,
 }
 
 impl ActionBuilder {
@@ -114,23 +324,74 @@ impl ActionBuilder {
         self.postconditions.insert::<T>(value);
         self
     }
+
+    /// Sets the cost of this `Action` until updated with `Action::update_cost`. Defaults to 1.
+    pub fn with_cost(mut self, cost: u32) -> ActionBuilder {
+        self.cost = cost as i32;
+        self
+    }
+
+    /// Sets the maximum duration this `Action` may spend starting and executing before it fails,
+    /// overriding `TimeoutSettings::execution`.
+    pub fn with_timeout(mut self, timeout: Duration) -> ActionBuilder {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Declares a resource channel this `Action` occupies while executing, e.g. `"legs"`, `"hands"`
+    /// or `"voice"`.
+    ///
+    /// Once an `Actor` starts a plan step, it also starts any later steps that occupy none of the
+    /// channels of the earlier unfinished steps, already have their preconditions met, and neither
+    /// read nor write the `Condition`s written by the earlier unfinished steps, so that several
+    /// `Action`s may be executing at once. An `Action` without channels never executes alongside
+    /// other `Action`s.
+    pub fn with_channel(mut self, channel: &'static str) -> ActionBuilder {
+        if !self.channels.contains(&channel) {
+            self.channels.push(channel);
+        }
+
+        self
+    }
 }
 
 pub trait BuildAction: Send + Sync {
     fn build(&self, commands: &mut Commands, actor_entity: Entity) -> Entity;
+
+    fn name(&self) -> &'static str;
+
+    fn preconditions(&self) -> &GoapState;
+
+    fn postconditions(&self) -> &GoapState;
 }
 
 impl BuildAction for ActionBuilder {
+    fn name(&self) -> &'static str {
+        self.marker_component.name()
+    }
+
+    fn preconditions(&self) -> &GoapState {
+        &self.preconditions
+    }
+
+    fn postconditions(&self) -> &GoapState {
+        &self.postconditions
+    }
+
     fn build(&self, commands: &mut Commands, actor_entity: Entity) -> Entity {
         let action_entity = commands
             .spawn_empty()
             .insert(Action {
                 actor_entity,
+                name: self.marker_component.name(),
                 preconditions: self.preconditions.clone(),
                 postconditions: self.postconditions.clone(),
-                cost: 1,
+                cost: self.cost,
+                timeout: self.timeout,
+                channels: self.channels.clone(),
             })
             .insert(ActionState::Idle)
+            .insert(LastValidActionState(ActionState::Idle))
             .id();
 
         self.marker_component.insert(commands, action_entity);
@@ -140,14 +401,161 @@ impl BuildAction for ActionBuilder {
 }
 
 #[allow(clippy::type_complexity)]
+pub fn validate_action_state_system(
+    mut query: Query<
+        (Entity, &Action, &mut ActionState, &mut LastValidActionState),
+        Changed<ActionState>,
+    >,
+    actors: Query<&Actor>,
+    mut ev_invalid_transition: EventWriter<InvalidActionTransition>,
+    log: Res<GoapLogSettings>,
+) {
+    for (action_entity, action, mut action_state, mut last_valid) in query.iter_mut() {
+        if *action_state == last_valid.0 {
+            continue;
+        }
+
+        // An action may only complete or fail if the actor is currently executing it.
+        let is_current_action = || {
+            !action.is_placeholder()
+                && actors.get(action.actor_entity).is_ok_and(|actor| {
+                    actor.started_actions.contains(&action_entity)
+                        || actor.is_current_sub_action(action_entity)
+                })
+        };
+
+        let is_valid = last_valid.0.can_transition_to(&action_state)
+            && (!matches!(*action_state, ActionState::Complete | ActionState::Failure)
+                || is_current_action());
+
+        if is_valid {
+            last_valid.0 = *action_state;
+        } else {
+            if log.actions >= GoapLogLevel::Info {
+                warn!(
+                    actor = ?action.actor_entity,
+                    action = ?action_entity,
+                    from = ?last_valid.0,
+                    to = ?*action_state,
+                    "Invalid ActionState transition reverted"
+                );
+            }
+
+            ev_invalid_transition.send(InvalidActionTransition {
+                action_entity,
+                actor_entity: action.actor_entity,
+                from: last_valid.0,
+                to: *action_state,
+            });
+
+            *action_state = last_valid.0;
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TimedPhase {
+    Evaluation,
+    Execution,
+}
+
+/// Fails any `Action` that has been evaluating or executing for longer than its timeout.
+pub fn action_timeout_system(
+    time: Option<Res<Time>>,
+    settings: Res<TimeoutSettings>,
+    mut query: Query<(Entity, &Action, ActionStateQuery)>,
+    mut phase_start_times: Local<HashMap<Entity, (TimedPhase, Duration)>>,
+    mut ev_timed_out: EventWriter<ActionTimedOut>,
+    log: Res<GoapLogSettings>,
+) {
+    let Some(time) = time else {
+        return;
+    };
+
+    let now = time.elapsed();
+
+    phase_start_times.retain(|action_entity, _| query.contains(*action_entity));
+
+    for (action_entity, action, mut action_state) in query.iter_mut() {
+        let (phase, timeout) = match action_state.get() {
+            ActionState::Evaluate => (TimedPhase::Evaluation, settings.evaluation),
+            ActionState::Started | ActionState::Executing => {
+                (TimedPhase::Execution, action.timeout.or(settings.execution))
+            }
+            _ => {
+                phase_start_times.remove(&action_entity);
+                continue;
+            }
+        };
+
+        let (_, start_time) = phase_start_times
+            .entry(action_entity)
+            .and_modify(|entry| {
+                if entry.0 != phase {
+                    *entry = (phase, now);
+                }
+            })
+            .or_insert((phase, now));
+
+        match timeout {
+            Some(timeout) if now.saturating_sub(*start_time) > timeout => {
+                phase_start_times.remove(&action_entity);
+
+                if log.actions >= GoapLogLevel::Info {
+                    info!(
+                        actor = ?action.actor_entity,
+                        action = ?action_entity,
+                        timeout_ms = timeout.as_millis() as u64,
+                        "Action timed out"
+                    );
+                }
+
+                match phase {
+                    TimedPhase::Evaluation => action_state
+                        .issue(ActionState::EvaluationComplete(EvaluationResult::Failure)),
+                    TimedPhase::Execution => {
+                        action_state.issue(ActionState::Failure);
+                        ev_timed_out.send(ActionTimedOut { action_entity });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Despawns any `Action` whose `Actor` no longer exists.
+pub fn despawn_orphaned_actions_system(
+    mut commands: Commands,
+    actions: Query<(Entity, &Action)>,
+    actors: Query<&Actor>,
+) {
+    for (action_entity, action) in actions.iter() {
+        if !action.is_placeholder() && !actors.contains(action.actor_entity) {
+            commands.entity(action_entity).despawn_recursive();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn action_state_system(
     mut actors: Query<&mut Actor>,
     mut actor_states: Query<&mut ActorState>,
     mut set: ParamSet<(
-        Query<(&Action, &mut ActionState), Changed<ActionState>>,
-        Query<&mut ActionState>,
+        Query<(Entity, &Action, ActionStateQuery), Changed<ActionState>>,
+        Query<ActionStateQuery>,
     )>,
+    actions: Query<&Action>,
+    mut events: LifecycleEvents,
+    mut ev_timed_out: EventReader<ActionTimedOut>,
+    log: Res<GoapLogSettings>,
+    mut histories: Query<&mut PlanHistory>,
 ) {
+    let timed_out = ev_timed_out
+        .iter()
+        .map(|ev| ev.action_entity)
+        .collect::<HashSet<_>>();
+
     let mut changed_action_states_query = set.p0();
 
     if changed_action_states_query.iter().count() == 0 {
@@ -155,19 +563,77 @@ pub fn action_state_system(
     }
 
     let mut completed = vec![];
+    let mut failed = vec![];
+
+    for (action_entity, action, mut action_state) in changed_action_states_query.iter_mut() {
+        if action.is_placeholder() {
+            continue;
+        }
 
-    for (action, mut action_state) in changed_action_states_query.iter_mut() {
-        match *action_state {
+        match action_state.get() {
             ActionState::Complete => {
-                *action_state = ActionState::Idle;
+                action_state.issue(ActionState::Idle);
+
+                if log.actions >= GoapLogLevel::Info {
+                    info!(actor = ?action.actor_entity, action = ?action_entity, "Action completed");
+                }
 
-                completed.push((action.actor_entity, action.postconditions.clone()));
+                events.action_completed.send(ActionCompleted {
+                    actor_entity: action.actor_entity,
+                    action_entity,
+                });
+
+                completed.push((
+                    action.actor_entity,
+                    action_entity,
+                    action.postconditions.clone(),
+                ));
             }
             ActionState::Failure => {
-                *action_state = ActionState::Idle;
+                action_state.issue(ActionState::Idle);
+
+                let reason = if timed_out.contains(&action_entity) {
+                    PlanFailureReason::ActionTimedOut(action_entity)
+                } else {
+                    PlanFailureReason::ActionFailed(action_entity)
+                };
+
+                if log.actions >= GoapLogLevel::Info {
+                    info!(actor = ?action.actor_entity, action = ?action_entity, "Action failed");
+                }
+
+                events.action_failed.send(ActionFailed {
+                    actor_entity: action.actor_entity,
+                    action_entity,
+                });
+
+                // Concurrent Actions may fail in the same update, but the plan fails once.
+                if failed
+                    .iter()
+                    .any(|(actor_entity, _)| *actor_entity == action.actor_entity)
+                {
+                    continue;
+                }
 
                 let mut actor_state = actor_states.get_mut(action.actor_entity).unwrap();
                 *actor_state = ActorState::FailedDuringPlan;
+
+                if let Ok(mut history) = histories.get_mut(action.actor_entity) {
+                    let actor = actors.get(action.actor_entity).unwrap();
+
+                    history.finish(PlanOutcome::Failed {
+                        step: actor.current_step().unwrap_or_default(),
+                        action: action.name.to_string(),
+                        reason: reason.clone(),
+                    });
+                }
+
+                events.plan_failed.send(PlanFailed {
+                    actor_entity: action.actor_entity,
+                    reason,
+                });
+
+                failed.push((action.actor_entity, action_entity));
             }
             _ => (),
         };
@@ -175,18 +641,91 @@ pub fn action_state_system(
 
     let mut all_action_states_query = set.p1();
 
-    for (actor_entity, postconditions) in completed {
+    for (actor_entity, failed_action_entity) in failed.iter().copied() {
+        let mut actor = actors.get_mut(actor_entity).unwrap();
+
+        // Actions executing alongside the failed Action are stopped with the plan.
+        for action_entity in actor.abandon_started_steps() {
+            if action_entity != failed_action_entity {
+                all_action_states_query
+                    .get_mut(action_entity)
+                    .unwrap()
+                    .issue(ActionState::Idle);
+            }
+        }
+
+        // A failure inside a sub-plan fails every enclosing CompoundAction.
+        for compound_action_entity in cancel_sub_plans(&mut actor, &mut all_action_states_query) {
+            events.action_failed.send(ActionFailed {
+                actor_entity,
+                action_entity: compound_action_entity,
+            });
+        }
+    }
+
+    for (actor_entity, action_entity, postconditions) in completed {
         let mut actor = actors.get_mut(actor_entity).unwrap();
 
-        if let Some(next_action_entity) = actor.complete_action(postconditions) {
-            let mut next_action_state = all_action_states_query
-                .get_mut(*next_action_entity)
-                .unwrap();
-            *next_action_state = ActionState::Started;
+        // The plan failed in the same update, so it is not continued or completed.
+        if failed
+            .iter()
+            .any(|(failed_actor, _)| *failed_actor == actor_entity)
+        {
+            actor.complete_action(action_entity, postconditions);
+            continue;
+        }
+
+        let next_action_entities = if actor.is_current_sub_action(action_entity) {
+            actor.complete_action(action_entity, postconditions);
+
+            actor
+                .sub_plans
+                .last()
+                .and_then(|sub_plan| sub_plan.path.front().copied())
+                .into_iter()
+                .collect()
         } else {
+            actor.complete_action(action_entity, postconditions);
+
+            let startable_steps = actor.startable_steps(&actions);
+            for step in startable_steps.iter() {
+                actor.start_action(*step);
+            }
+            startable_steps
+        };
+
+        for next_action_entity in next_action_entities {
+            let mut next_action_state =
+                all_action_states_query.get_mut(next_action_entity).unwrap();
+            next_action_state.issue(ActionState::Started);
+
+            if log.actions >= GoapLogLevel::Verbose {
+                debug!(actor = ?actor_entity, action = ?next_action_entity, "Action started");
+            }
+
+            events.action_started.send(ActionStarted {
+                actor_entity,
+                action_entity: next_action_entity,
+            });
+        }
+
+        if actor.current_path.is_empty() && actor.sub_plans.is_empty() {
             // The actor completed an action and there is no next one, so they completed their current plan.
             let mut actor_state = actor_states.get_mut(actor_entity).unwrap();
             *actor_state = ActorState::CompletedPlan;
+
+            if let Ok(mut history) = histories.get_mut(actor_entity) {
+                history.finish(PlanOutcome::Completed);
+            }
+
+            if log.actions >= GoapLogLevel::Info {
+                info!(actor = ?actor_entity, "Plan completed");
+            }
+
+            events.plan_completed.send(PlanCompleted { actor_entity });
         }
+        // Otherwise, if no Action was started, either other Actions are still executing, or the
+        // sub-plan of a CompoundAction completed, which completes the CompoundAction in
+        // `compound_action_system`.
     }
 }
@@ -0,0 +1,135 @@
+use bevy::{
+    ecs::system::{StaticSystemParam, SystemParam, SystemParamItem},
+    prelude::{Component, Entity, Local, Query},
+    utils::HashSet,
+};
+
+use crate::action::{Action, ActionState, EvaluationResult};
+
+/// The progress of an `Action` that has been started, as reported by `GoapAction::on_start` and
+/// `GoapAction::tick`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ActionProgress {
+    /// The `Action` is still executing, and will be ticked again on the next update.
+    Running,
+    /// The `Action` completed successfully.
+    Complete,
+    /// The `Action` failed and the `Actor` requires a replan.
+    Failure,
+}
+
+impl From<ActionProgress> for ActionState {
+    fn from(progress: ActionProgress) -> Self {
+        match progress {
+            ActionProgress::Running => ActionState::Executing,
+            ActionProgress::Complete => ActionState::Complete,
+            ActionProgress::Failure => ActionState::Failure,
+        }
+    }
+}
+
+/// A trait for `Action` marker components that lets the crate drive the `ActionState` transitions
+/// of the `Action`, calling into the hooks below.
+///
+/// Register an implementation with `GoapAppExt::add_goap_action`, rather than writing an `Action`
+/// system that matches on `ActionState` by hand.
+///
+/// Each hook is given the `SystemParam` declared by `Param`, which must not access `Action` or
+/// `ActionState` components, as these are already accessed by the driving system.
+pub trait GoapAction: Component {
+    /// The system parameters required by the hooks, e.g. `(SCommands, SQuery<Read<Transform>>)`
+    /// using the types in `bevy::ecs::system::lifetimeless`.
+    type Param: SystemParam + 'static;
+
+    /// Called in `ActionState::Evaluate`, to determine whether this `Action` is viable to be
+    /// included in the next plan. The `Action` cost may be updated here.
+    ///
+    /// Defaults to `EvaluationResult::Success`.
+    fn evaluate(
+        &mut self,
+        _action: &mut Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> EvaluationResult {
+        EvaluationResult::Success
+    }
+
+    /// Called in `ActionState::Started`, to perform any initialisation required for the execution
+    /// of this `Action`.
+    ///
+    /// Defaults to `ActionProgress::Running`, so `tick` is called from the next update.
+    fn on_start(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Running
+    }
+
+    /// Called every update while this `Action` is executing, to check whether it has completed.
+    fn tick(&mut self, action: &Action, param: &mut SystemParamItem<Self::Param>)
+        -> ActionProgress;
+
+    /// Called if this `Action` was started, but the `Actor` abandoned its plan before the `Action`
+    /// completed or failed, e.g. because the `Actor` requires a new plan.
+    fn on_cancel(&mut self, _action: &Action, _param: &mut SystemParamItem<Self::Param>) {}
+
+    /// Called in `ActionState::NotInPlan`, to perform any cleanup from the evaluation phase.
+    /// `evaluated` indicates whether `evaluate` had been called.
+    fn on_not_in_plan(
+        &mut self,
+        _action: &Action,
+        _evaluated: bool,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) {
+    }
+}
+
+/// Drives the `ActionState` of every `Action` with the `GoapAction` marker component `T`.
+pub(crate) fn goap_action_system<T: GoapAction>(
+    mut query: Query<(Entity, &mut T, &mut Action, &mut ActionState)>,
+    mut running: Local<HashSet<Entity>>,
+    param: StaticSystemParam<T::Param>,
+) {
+    let mut param = param.into_inner();
+
+    // Forget about any running actions that have since been despawned.
+    running.retain(|action_entity| query.contains(*action_entity));
+
+    for (action_entity, mut handler, mut action, mut action_state) in query.iter_mut() {
+        if !matches!(*action_state, ActionState::Started | ActionState::Executing)
+            && running.remove(&action_entity)
+        {
+            handler.on_cancel(&action, &mut param);
+        }
+
+        match *action_state {
+            ActionState::Evaluate => {
+                let result = handler.evaluate(&mut action, &mut param);
+                *action_state = ActionState::EvaluationComplete(result);
+            }
+            ActionState::NotInPlan(evaluated) => {
+                handler.on_not_in_plan(&action, evaluated, &mut param);
+                *action_state = ActionState::Idle;
+            }
+            ActionState::Started => {
+                let progress = handler.on_start(&action, &mut param);
+
+                if progress == ActionProgress::Running {
+                    running.insert(action_entity);
+                }
+
+                *action_state = progress.into();
+            }
+            ActionState::Executing => {
+                let progress = handler.tick(&action, &mut param);
+
+                // Avoid triggering change detection when the action is still executing.
+                if progress != ActionProgress::Running {
+                    running.remove(&action_entity);
+                    *action_state = progress.into();
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,17 +1,95 @@
 use std::{collections::VecDeque, sync::Arc};
 
-use bevy::prelude::{Added, Changed, Commands, Component, Entity, EventWriter, Query};
+use bevy::{
+    ecs::{
+        entity::{EntityMap, MapEntities, MapEntitiesError},
+        reflect::ReflectMapEntities,
+    },
+    log::{debug, info, warn},
+    prelude::{
+        Added, Changed, Commands, Component, DetectChanges, Entity, EventWriter, FromReflect,
+        Query, Reflect, ReflectComponent, Res,
+    },
+};
+
+#[cfg(feature = "serde")]
+use bevy::reflect::{ReflectDeserialize, ReflectSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    action::BuildAction, common::MarkerComponent, state::GoapState, Condition, RequestPlanEvent,
+    action::{Action, BuildAction},
+    analysis::DomainReport,
+    blackboard::Blackboard,
+    common::MarkerComponent,
+    compound::SubPlan,
+    dot::write_domain,
+    events::GoalChanged,
+    settings::{GoapLogLevel, GoapLogSettings},
+    state::GoapState,
+    Condition, RequestPlanEvent,
 };
 
-#[derive(Component, Debug)]
+/// Reflected as an opaque value, since its states are keyed by `Condition` names. Scenes containing
+/// `Actor`s can only be serialized with the `serde` feature.
+#[derive(Component, Reflect, Clone, Debug, Default)]
+#[cfg_attr(not(feature = "serde"), reflect_value(Component, MapEntities))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    reflect_value(Component, MapEntities, Serialize, Deserialize)
+)]
 pub struct Actor {
     pub(crate) actions: Vec<Entity>,
     pub(crate) current_path: VecDeque<Entity>,
+    /// The index into `planned_actions` of each step of `current_path`, as an `Action` may occur
+    /// more than once in a plan.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) path_steps: VecDeque<usize>,
+    /// The `Action`s of `current_path` that have been started, see `ActionBuilder::with_channel`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) started_actions: Vec<Entity>,
+    pub(crate) current_plan: Option<Plan>,
     pub(crate) current_state: GoapState,
     pub(crate) current_goal: GoapState,
+    /// The sub-plans of the `CompoundAction`s being executed, from the outermost to the innermost.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) sub_plans: Vec<SubPlan>,
+    /// The current state as last observed by `replan_on_change_system`, updated with the effects of
+    /// the plan, see `PlannerSettings::replan_on_change`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) observed_state: Option<GoapState>,
+    /// Whether the next plan must be searched for from scratch rather than repaired, because a
+    /// cheaper plan is available, see `PlannerSettings::repair_plans`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) skip_repair: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    goal_changed: bool,
+}
+
+impl MapEntities for Actor {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        for entity in self
+            .actions
+            .iter_mut()
+            .chain(self.current_path.iter_mut())
+            .chain(self.started_actions.iter_mut())
+            .chain(
+                self.current_plan
+                    .iter_mut()
+                    .flat_map(|plan| plan.steps.iter_mut()),
+            )
+            .chain(self.sub_plans.iter_mut().flat_map(|sub_plan| {
+                std::iter::once(&mut sub_plan.compound_action)
+                    .chain(sub_plan.plan.steps.iter_mut())
+                    .chain(sub_plan.path.iter_mut())
+            }))
+        {
+            *entity = entity_map.get(*entity)?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Actor {
@@ -28,15 +106,235 @@ impl Actor {
         self.current_state.insert::<T>(value);
     }
 
-    pub(crate) fn complete_action(&mut self, postconditions: GoapState) -> Option<&Entity> {
+    /// Sets the value of a `Condition` in the goal of this `Actor`. This does not trigger a new
+    /// plan by itself, so you may wish to transition the `Actor` to `ActorState::RequiresPlan`.
+    pub fn update_goal<T: Condition + 'static>(&mut self, _condition: T, value: bool) {
+        self.current_goal.insert::<T>(value);
+        self.goal_changed = true;
+    }
+
+    /// Removes a `Condition` from the goal of this `Actor`.
+    pub fn remove_goal<T: Condition + 'static>(&mut self, _condition: T) {
+        self.current_goal.remove::<T>();
+        self.goal_changed = true;
+    }
+
+    /// The `Action` entities of this `Actor`.
+    pub fn actions(&self) -> &[Entity] {
+        &self.actions
+    }
+
+    /// The `Action` entities of the current plan, in the order they are executed. This is empty if
+    /// the `Actor` has no plan.
+    pub fn planned_actions(&self) -> &[Entity] {
+        self.current_plan
+            .as_ref()
+            .map_or(&[], |plan| plan.steps.as_slice())
+    }
+
+    /// The index into `planned_actions` of the `Action` currently being executed, or `None` if no
+    /// plan is in progress.
+    pub fn current_step(&self) -> Option<usize> {
+        self.path_steps.front().copied()
+    }
+
+    /// The `Action` entity currently being executed, or `None` if no plan is in progress. While a
+    /// `CompoundAction` is executing, this is the sub-action currently being executed, whereas
+    /// `current_step` remains the index of the `CompoundAction`.
+    pub fn current_action(&self) -> Option<Entity> {
+        match self.sub_plans.last() {
+            Some(sub_plan) => sub_plan.path.front().copied(),
+            None => self.current_path.front().copied(),
+        }
+    }
+
+    /// The `Action` entities currently being executed, in plan order. This contains several
+    /// `Action`s if they occupy different channels, see `ActionBuilder::with_channel`, followed by
+    /// the current sub-action of each `CompoundAction` being executed.
+    pub fn executing_actions(&self) -> Vec<Entity> {
+        self.started_actions
+            .iter()
+            .copied()
+            .chain(
+                self.sub_plans
+                    .iter()
+                    .filter_map(|sub_plan| sub_plan.path.front().copied()),
+            )
+            .collect()
+    }
+
+    /// The sub-plans of the `CompoundAction`s being executed, from the outermost to the innermost.
+    /// This is empty unless the current `Action` is a `CompoundAction`.
+    pub fn sub_plans(&self) -> &[SubPlan] {
+        &self.sub_plans
+    }
+
+    /// The total cost of the `Action`s in the current plan, or `None` if the `Actor` has no plan.
+    pub fn plan_cost(&self) -> Option<i32> {
+        self.current_plan.as_ref().map(|plan| plan.cost)
+    }
+
+    /// The name and value of each `Condition` in the state this `Actor` is expected to reach by
+    /// completing its current plan. This is empty if the `Actor` has no plan.
+    pub fn expected_end_state(&self) -> impl Iterator<Item = (&'static str, bool)> + '_ {
+        self.current_plan
+            .iter()
+            .flat_map(|plan| plan.expected_state.iter())
+    }
+
+    /// The name and value of each `Condition` in the current state of this `Actor`.
+    pub fn current_state(&self) -> impl Iterator<Item = (&'static str, bool)> + '_ {
+        self.current_state.iter()
+    }
+
+    /// The name and value of each `Condition` in the goal of this `Actor`.
+    pub fn goal(&self) -> impl Iterator<Item = (&'static str, bool)> + '_ {
+        self.current_goal.iter()
+    }
+
+    pub(crate) fn set_plan(&mut self, plan: Option<Plan>) {
+        self.current_path = plan
+            .as_ref()
+            .map(|plan| VecDeque::from_iter(plan.steps.iter().copied()))
+            .unwrap_or_default();
+        self.path_steps = (0..self.current_path.len()).collect();
+        self.current_plan = plan;
+        self.started_actions.clear();
+        self.sub_plans.clear();
+        self.observed_state = Some(self.current_state.clone());
+        self.skip_repair = false;
+    }
+
+    /// Marks a step of the current plan as started.
+    pub(crate) fn start_action(&mut self, action_entity: Entity) {
+        self.started_actions.push(action_entity);
+    }
+
+    /// Removes the started steps from the current plan after it failed, so that repairing the plan
+    /// does not keep them, and returns their `Action`s.
+    pub(crate) fn abandon_started_steps(&mut self) -> Vec<Entity> {
+        let started_actions = std::mem::take(&mut self.started_actions);
+
+        for action_entity in started_actions.iter() {
+            if let Some(index) = self
+                .current_path
+                .iter()
+                .position(|step| step == action_entity)
+            {
+                self.current_path.remove(index);
+                self.path_steps.remove(index);
+            }
+        }
+
+        started_actions
+    }
+
+    /// The steps of the current plan that can be started now, in plan order: the first remaining
+    /// step, and any later step whose preconditions are met and which can execute alongside every
+    /// earlier remaining step, see `ActionBuilder::with_channel`. Steps that have already started
+    /// are excluded.
+    pub(crate) fn startable_steps(&self, actions: &Query<&Action>) -> Vec<Entity> {
+        let mut earlier_steps: Vec<&Action> = vec![];
+        let mut startable_steps = vec![];
+
+        for (index, step) in self.current_path.iter().enumerate() {
+            let Ok(action) = actions.get(*step) else {
+                break;
+            };
+
+            let can_start = index == 0
+                || (self
+                    .current_state
+                    .state
+                    .satisfies(&action.preconditions.state)
+                    && earlier_steps
+                        .iter()
+                        .all(|earlier_step| action.can_execute_alongside(earlier_step)));
+
+            if can_start && !self.started_actions.contains(step) {
+                startable_steps.push(*step);
+            }
+
+            earlier_steps.push(action);
+        }
+
+        startable_steps
+    }
+
+    /// Whether the remaining steps of the current plan can still be executed in order from the
+    /// current state, reaching the goal.
+    ///
+    /// The preconditions of started steps are not checked again, as a started step may already have
+    /// changed the current state, e.g. a "get axe" `Action` requiring that the `Actor` has no axe.
+    pub(crate) fn is_plan_valid(&self, actions: &Query<&Action>) -> bool {
+        let mut expected_state = self.current_state.clone();
+
+        for (index, step) in self.current_path.iter().enumerate() {
+            let Ok(action) = actions.get(*step) else {
+                return false;
+            };
+
+            let is_started = index == 0 || self.started_actions.contains(step);
+
+            if !is_started && !expected_state.state.satisfies(&action.preconditions.state) {
+                return false;
+            }
+
+            expected_state.extend(action.postconditions.clone());
+        }
+
+        expected_state.state.satisfies(&self.current_goal.state)
+    }
+
+    /// Whether `action_entity` is the current sub-action of the innermost sub-plan.
+    pub(crate) fn is_current_sub_action(&self, action_entity: Entity) -> bool {
+        self.sub_plans
+            .last()
+            .is_some_and(|sub_plan| sub_plan.path.front() == Some(&action_entity))
+    }
+
+    /// Completes `action_entity`, which is either a started step of the current plan, or the
+    /// current sub-action of the innermost sub-plan.
+    pub(crate) fn complete_action(&mut self, action_entity: Entity, postconditions: GoapState) {
+        if let Some(observed_state) = self.observed_state.as_mut() {
+            observed_state.extend(postconditions.clone());
+        }
+
         self.current_state.extend(postconditions);
-        self.current_path.pop_front();
-        self.current_path.front()
+
+        if self.is_current_sub_action(action_entity) {
+            self.sub_plans.last_mut().unwrap().path.pop_front();
+            return;
+        }
+
+        self.started_actions
+            .retain(|started| *started != action_entity);
+
+        if let Some(index) = self
+            .current_path
+            .iter()
+            .position(|step| *step == action_entity)
+        {
+            self.current_path.remove(index);
+            self.path_steps.remove(index);
+        }
     }
 }
 
-#[derive(Component, PartialEq, Eq, Debug, Clone)]
+/// A plan created for an `Actor`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct Plan {
+    pub(crate) steps: Vec<Entity>,
+    pub(crate) cost: i32,
+    pub(crate) expected_state: GoapState,
+}
+
+#[derive(Component, Reflect, FromReflect, PartialEq, Eq, Debug, Clone, Default)]
+#[reflect(Component, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ActorState {
+    #[default]
     RequiresPlan,
     AwaitingPlan,
     NoPlanAvailable,
@@ -47,10 +345,10 @@ pub enum ActorState {
 
 #[derive(Component, Clone)]
 pub struct ActorBuilder {
-    marker_component: Arc<dyn MarkerComponent>,
-    actions: Vec<Arc<dyn BuildAction>>,
-    initial_state: GoapState,
-    initial_goal: GoapState,
+    pub(crate) marker_component: Arc<dyn MarkerComponent>,
+    pub(crate) actions: Vec<Arc<dyn BuildAction>>,
+    pub(crate) initial_state: GoapState,
+    pub(crate) initial_goal: GoapState,
 }
 
 impl ActorBuilder {
@@ -73,6 +371,30 @@ impl ActorBuilder {
         self
     }
 
+    /// Checks the `Action`s and goal of this `Actor` for issues that would prevent or hinder
+    /// planning, e.g. in a test. Each `Actor` is also validated when it is built, logging any
+    /// issues as warnings if `GoapLogSettings::actors` is at least `GoapLogLevel::Info`.
+    pub fn validate(&self) -> DomainReport {
+        DomainReport::validate(&self.initial_state, &self.initial_goal, &self.actions)
+    }
+
+    /// Exports the `Action`s of this `Actor` as a Graphviz DOT graph, see `domain_to_dot`.
+    pub fn to_dot(&self) -> String {
+        write_domain(
+            &self
+                .actions
+                .iter()
+                .map(|action| {
+                    (
+                        action.name(),
+                        action.preconditions(),
+                        action.postconditions(),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
     fn build(&self, commands: &mut Commands, actor_entity: Entity) {
         let action_entities = self
             .actions
@@ -85,10 +407,18 @@ impl ActorBuilder {
             .insert(Actor {
                 actions: action_entities,
                 current_path: VecDeque::new(),
+                path_steps: VecDeque::new(),
+                started_actions: vec![],
+                current_plan: None,
                 current_state: self.initial_state.clone(),
                 current_goal: self.initial_goal.clone(),
+                sub_plans: vec![],
+                observed_state: None,
+                skip_repair: false,
+                goal_changed: false,
             })
             .insert(ActorState::RequiresPlan)
+            .insert(Blackboard::default())
             .remove::<ActorBuilder>();
 
         self.marker_component.insert(commands, actor_entity);
@@ -98,10 +428,17 @@ impl ActorBuilder {
 pub fn build_new_actor_system(
     mut commands: Commands,
     query: Query<(Entity, &ActorBuilder), Added<ActorBuilder>>,
+    log: Res<GoapLogSettings>,
 ) {
     for (entity, actor_builder) in query.iter() {
         let actor_entity = commands.entity(entity).id();
 
+        if log.actors >= GoapLogLevel::Info {
+            for issue in actor_builder.validate().issues {
+                warn!(actor = ?actor_entity, ?issue, "Invalid Actor domain");
+            }
+        }
+
         actor_builder.build(&mut commands, actor_entity);
     }
 }
@@ -109,11 +446,34 @@ pub fn build_new_actor_system(
 pub fn actor_state_system(
     mut query: Query<(Entity, &mut ActorState), Changed<ActorState>>,
     mut ev_request_plan: EventWriter<RequestPlanEvent>,
+    log: Res<GoapLogSettings>,
 ) {
     for (actor_entity, mut actor_state) in query.iter_mut() {
+        if log.actors >= GoapLogLevel::Verbose {
+            debug!(actor = ?actor_entity, state = ?*actor_state, "ActorState changed");
+        }
+
         if let ActorState::RequiresPlan = *actor_state {
             *actor_state = ActorState::AwaitingPlan;
             ev_request_plan.send(RequestPlanEvent(actor_entity));
         };
     }
 }
+
+pub fn goal_changed_system(
+    mut query: Query<(Entity, &mut Actor), Changed<Actor>>,
+    mut ev_goal_changed: EventWriter<GoalChanged>,
+    log: Res<GoapLogSettings>,
+) {
+    for (actor_entity, mut actor) in query.iter_mut() {
+        if actor.goal_changed {
+            actor.bypass_change_detection().goal_changed = false;
+
+            if log.actors >= GoapLogLevel::Info {
+                info!(actor = ?actor_entity, goal = ?actor.goal().collect::<Vec<_>>(), "Goal changed");
+            }
+
+            ev_goal_changed.send(GoalChanged { actor_entity });
+        }
+    }
+}
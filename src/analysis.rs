@@ -0,0 +1,259 @@
+use std::sync::Arc;
+
+use bevy::prelude::{Component, Entity};
+
+use crate::{
+    action::{Action, BuildAction, EvaluationResult},
+    planner::{self, Reachability},
+    state::{ConditionId, GoapState},
+};
+
+/// The `Condition` values that can be reached from a state, when ignoring that `Action`s may undo
+/// each other's postconditions, see `planner::Reachability`.
+pub(crate) struct RelaxedReachability {
+    reachability: Reachability<ConditionId>,
+}
+
+impl RelaxedReachability {
+    /// Analyses the reachability from `start`, given the conditions of each `Action`.
+    pub(crate) fn analyse<'a>(
+        start: &GoapState,
+        actions: impl IntoIterator<Item = (&'a GoapState, &'a GoapState)> + Clone,
+    ) -> Self {
+        Self {
+            reachability: Reachability::analyse(
+                &start.state,
+                actions
+                    .into_iter()
+                    .map(|(preconditions, postconditions)| {
+                        (&preconditions.state, &postconditions.state)
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+        }
+    }
+
+    /// The name and value of each `Condition` in `state` that is not reachable.
+    pub(crate) fn unreachable(&self, state: &GoapState) -> Vec<(&'static str, bool)> {
+        sorted_names(self.reachability.unreachable(&state.state))
+    }
+}
+
+/// The name and value of each `Condition` in `target` that is neither met by `start`, nor is a
+/// postcondition of any `Action`.
+pub(crate) fn unproducible<'a>(
+    target: &GoapState,
+    start: &GoapState,
+    postconditions: impl IntoIterator<Item = &'a GoapState> + Clone,
+) -> Vec<(&'static str, bool)> {
+    let postconditions = postconditions
+        .into_iter()
+        .map(|state| &state.state)
+        .collect::<Vec<_>>();
+
+    sorted_names(planner::unproducible(
+        &target.state,
+        &start.state,
+        postconditions.iter().copied(),
+    ))
+}
+
+fn sorted_names(values: Vec<(ConditionId, bool)>) -> Vec<(&'static str, bool)> {
+    let mut names = values
+        .into_iter()
+        .map(|(condition, value)| (condition.name(), value))
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// An `Action` that was not considered by the planner, due to its `EvaluationResult`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ExcludedAction {
+    pub action_entity: Entity,
+    pub name: &'static str,
+    pub result: EvaluationResult,
+}
+
+/// An `Action` that could never be started, due to preconditions that cannot be reached.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UnreachablePreconditions {
+    pub action_entity: Entity,
+    pub name: &'static str,
+    /// The name and value of each unreachable precondition.
+    pub preconditions: Vec<(&'static str, bool)>,
+}
+
+/// Explains why the planner could not find a plan for an `Actor`. This is inserted on the `Actor`'s
+/// entity when it enters `ActorState::NoPlanAvailable`, and removed once a plan is created.
+///
+/// The analysis ignores that `Action`s may undo each other's postconditions. If every list is
+/// empty, the goal is reachable in that relaxed sense, so the plan was prevented by conflicting
+/// postconditions, or by `PlannerSettings::max_expanded_nodes`.
+#[derive(Component, Clone, PartialEq, Eq, Debug, Default)]
+pub struct NoPlanExplanation {
+    /// The name and value of each goal `Condition` that is not met by the current state, and is not
+    /// a postcondition of any evaluated `Action`.
+    pub unproducible_goals: Vec<(&'static str, bool)>,
+    /// The name and value of each goal `Condition` that cannot be reached from the current state.
+    pub unreachable_goals: Vec<(&'static str, bool)>,
+    /// The `Action`s that failed or skipped evaluation.
+    pub excluded_actions: Vec<ExcludedAction>,
+    /// The evaluated `Action`s that could never be started.
+    pub unreachable_preconditions: Vec<UnreachablePreconditions>,
+}
+
+impl NoPlanExplanation {
+    /// Explains why no plan reaches `goal` from `start`, given each `Action` of the `Actor` with
+    /// its `EvaluationResult`.
+    pub(crate) fn explain(
+        start: &GoapState,
+        goal: &GoapState,
+        actions: &[(Entity, &Action, EvaluationResult)],
+    ) -> Self {
+        let evaluated_actions = actions
+            .iter()
+            .filter(|(_, _, result)| *result == EvaluationResult::Success)
+            .map(|(action_entity, action, _)| (*action_entity, *action))
+            .collect::<Vec<_>>();
+
+        let reachability = RelaxedReachability::analyse(
+            start,
+            evaluated_actions
+                .iter()
+                .map(|(_, action)| (&action.preconditions, &action.postconditions)),
+        );
+
+        let excluded_actions = actions
+            .iter()
+            .filter(|(_, _, result)| *result != EvaluationResult::Success)
+            .map(|(action_entity, action, result)| ExcludedAction {
+                action_entity: *action_entity,
+                name: action.name,
+                result: *result,
+            })
+            .collect();
+
+        let unreachable_preconditions = evaluated_actions
+            .iter()
+            .filter_map(|(action_entity, action)| {
+                let preconditions = reachability.unreachable(&action.preconditions);
+
+                (!preconditions.is_empty()).then_some(UnreachablePreconditions {
+                    action_entity: *action_entity,
+                    name: action.name,
+                    preconditions,
+                })
+            })
+            .collect();
+
+        Self {
+            unproducible_goals: unproducible(
+                goal,
+                start,
+                evaluated_actions
+                    .iter()
+                    .map(|(_, action)| &action.postconditions),
+            ),
+            unreachable_goals: reachability.unreachable(goal),
+            excluded_actions,
+            unreachable_preconditions,
+        }
+    }
+}
+
+/// An issue found when validating the `Action`s and goal of an `Actor`. `Action`s are referred to
+/// by their index in the order they were added to the `ActorBuilder`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DomainIssue {
+    /// A goal `Condition` is neither met by the initial state nor a postcondition of any `Action`.
+    UnproducibleGoal {
+        condition: &'static str,
+        value: bool,
+    },
+    /// A precondition of an `Action` cannot be reached from the initial state.
+    UnsatisfiablePrecondition {
+        action: usize,
+        name: &'static str,
+        condition: &'static str,
+        value: bool,
+    },
+    /// An `Action` has the same marker component and conditions as an earlier `Action`.
+    DuplicateAction {
+        action: usize,
+        duplicate_of: usize,
+        name: &'static str,
+    },
+    /// An `Action` has no postconditions, so it can never help to reach a goal.
+    EmptyPostconditions { action: usize, name: &'static str },
+}
+
+/// The result of validating the `Action`s and goal of an `Actor`, see `ActorBuilder::validate`.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct DomainReport {
+    pub issues: Vec<DomainIssue>,
+}
+
+impl DomainReport {
+    pub(crate) fn validate(
+        initial_state: &GoapState,
+        goal: &GoapState,
+        actions: &[Arc<dyn BuildAction>],
+    ) -> Self {
+        let mut issues = vec![];
+
+        for (condition, value) in unproducible(
+            goal,
+            initial_state,
+            actions.iter().map(|action| action.postconditions()),
+        ) {
+            issues.push(DomainIssue::UnproducibleGoal { condition, value });
+        }
+
+        let reachability = RelaxedReachability::analyse(
+            initial_state,
+            actions
+                .iter()
+                .map(|action| (action.preconditions(), action.postconditions())),
+        );
+
+        for (index, action) in actions.iter().enumerate() {
+            for (condition, value) in reachability.unreachable(action.preconditions()) {
+                issues.push(DomainIssue::UnsatisfiablePrecondition {
+                    action: index,
+                    name: action.name(),
+                    condition,
+                    value,
+                });
+            }
+
+            let duplicate_of = actions[..index].iter().position(|other| {
+                other.name() == action.name()
+                    && other.preconditions() == action.preconditions()
+                    && other.postconditions() == action.postconditions()
+            });
+
+            if let Some(duplicate_of) = duplicate_of {
+                issues.push(DomainIssue::DuplicateAction {
+                    action: index,
+                    duplicate_of,
+                    name: action.name(),
+                });
+            }
+
+            if action.postconditions().entries().next().is_none() {
+                issues.push(DomainIssue::EmptyPostconditions {
+                    action: index,
+                    name: action.name(),
+                });
+            }
+        }
+
+        Self { issues }
+    }
+
+    /// Whether no issues were found.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
@@ -0,0 +1,224 @@
+use bevy::{
+    ecs::schedule::ShouldRun,
+    prelude::{App, IntoSystem, IntoSystemDescriptor, Schedule, SystemSet, SystemStage},
+};
+
+use crate::{
+    action_handler::{goap_action_system, GoapAction},
+    planning::replan_on_change_system,
+    sensor::{sensor_system, Sensor, SensorUpdate},
+    GoapScheduleLabel, GoapStage, InternalGoapStage,
+};
+#[cfg(feature = "assets")]
+use crate::{
+    common::MarkerComponent,
+    domain::{domain_actor_system, GoapMarkerRegistry},
+};
+#[cfg(feature = "serde")]
+use crate::{
+    state::{register_condition, ConditionNameConflict},
+    Condition,
+};
+
+/// Extension methods for registering GOAP systems on a `Schedule` containing the `GoapStage`s, e.g.
+/// the `Schedule` created by `GoapPlugin::create_schedule` when using `GoapSchedule::Manual`.
+pub trait GoapScheduleExt {
+    /// Adds a system to one of the `GoapStage`s.
+    fn add_goap_system<Params>(
+        &mut self,
+        stage: GoapStage,
+        system: impl IntoSystemDescriptor<Params>,
+    ) -> &mut Self;
+
+    /// Adds a system set to one of the `GoapStage`s.
+    fn add_goap_system_set(&mut self, stage: GoapStage, system_set: SystemSet) -> &mut Self;
+
+    /// Adds a system to `GoapStage::Actions` that drives the `ActionState` of every `Action` with
+    /// the `GoapAction` marker component `T`.
+    fn add_goap_action<T: GoapAction>(&mut self) -> &mut Self;
+
+    /// Adds a system that updates the current state of every `Actor` with the `Sensor` `T`, in
+    /// `GoapStage::Sensors`, or right after it for `SensorUpdate::BeforePlanning` `Sensor`s.
+    fn add_goap_sensor<T: Sensor>(&mut self) -> &mut Self;
+
+    /// Adds the system to `GoapStage::Actors` that keeps `DomainActor`s up to date. The
+    /// `GoapAssetPlugin` does this itself, unless the `GoapPlugin` was added with
+    /// `GoapSchedule::Manual`.
+    #[cfg(feature = "assets")]
+    fn add_goap_domain_actors(&mut self) -> &mut Self;
+}
+
+impl GoapScheduleExt for Schedule {
+    fn add_goap_system<Params>(
+        &mut self,
+        stage: GoapStage,
+        system: impl IntoSystemDescriptor<Params>,
+    ) -> &mut Self {
+        self.add_system_to_stage(stage, system)
+    }
+
+    fn add_goap_system_set(&mut self, stage: GoapStage, system_set: SystemSet) -> &mut Self {
+        self.add_system_set_to_stage(stage, system_set)
+    }
+
+    fn add_goap_action<T: GoapAction>(&mut self) -> &mut Self {
+        self.add_goap_system(GoapStage::Actions, goap_action_system::<T>)
+    }
+
+    fn add_goap_sensor<T: Sensor>(&mut self) -> &mut Self {
+        match T::UPDATE {
+            SensorUpdate::EveryUpdate => {
+                self.add_goap_system(GoapStage::Sensors, sensor_system::<T>)
+            }
+            SensorUpdate::BeforePlanning => self.add_system_to_stage(
+                InternalGoapStage::PlanningSensors,
+                sensor_system::<T>.after(replan_on_change_system),
+            ),
+        }
+    }
+
+    #[cfg(feature = "assets")]
+    fn add_goap_domain_actors(&mut self) -> &mut Self {
+        self.add_goap_system(GoapStage::Actors, domain_actor_system)
+    }
+}
+
+/// Extension methods for registering GOAP systems on an `App`.
+///
+/// The methods adding systems find the `GoapStage`s wherever the `GoapPlugin` added them.
+///
+/// # Panics
+///
+/// The methods adding systems panic if the `GoapPlugin` was added with `GoapSchedule::Manual`, as
+/// the `GoapStage`s are then not part of the `App`'s schedule. Use `GoapScheduleExt` on the
+/// `Schedule` created by `GoapPlugin::create_schedule` instead.
+pub trait GoapAppExt {
+    /// Adds a system to one of the `GoapStage`s, wherever the crate's systems run.
+    fn add_goap_system<Params>(
+        &mut self,
+        stage: GoapStage,
+        system: impl IntoSystemDescriptor<Params>,
+    ) -> &mut Self;
+
+    /// Adds a system set to one of the `GoapStage`s, wherever the crate's systems run.
+    fn add_goap_system_set(&mut self, stage: GoapStage, system_set: SystemSet) -> &mut Self;
+
+    /// Adds a system to `GoapStage::Actions` that drives the `ActionState` of every `Action` with
+    /// the `GoapAction` marker component `T`.
+    fn add_goap_action<T: GoapAction>(&mut self) -> &mut Self;
+
+    /// Adds a system that updates the current state of every `Actor` with the `Sensor` `T`, in
+    /// `GoapStage::Sensors`, or right after it for `SensorUpdate::BeforePlanning` `Sensor`s.
+    fn add_goap_sensor<T: Sensor>(&mut self) -> &mut Self;
+
+    /// Registers a `Condition` by its name, so that states containing it can be deserialized, and
+    /// so that `GoapDomain`s loaded afterwards refer to it.
+    ///
+    /// Returns an error if a different `Condition` type is already registered with the same name.
+    #[cfg(feature = "serde")]
+    fn register_goap_condition<T: Condition + 'static>(
+        &mut self,
+    ) -> Result<&mut Self, ConditionNameConflict>;
+
+    /// Registers a marker component in the `GoapMarkerRegistry` as `name`, so that `GoapDomain`s
+    /// can refer to it.
+    #[cfg(feature = "assets")]
+    fn register_goap_marker(
+        &mut self,
+        name: impl Into<String>,
+        marker_component: impl MarkerComponent + 'static,
+    ) -> &mut Self;
+
+    /// Sets the run criteria of the crate's `Schedule`, e.g. a `FixedTimestep` or `run_in_state`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `GoapPlugin` was not added with `GoapSchedule::Nested`.
+    fn set_goap_run_criteria<P>(
+        &mut self,
+        run_criteria: impl IntoSystem<(), ShouldRun, P>,
+    ) -> &mut Self;
+}
+
+/// Whether the `GoapPlugin` added the `GoapStage`s to `schedule`, either directly or in the crate's
+/// own `Schedule`.
+pub(crate) fn has_goap_stages(schedule: &Schedule) -> bool {
+    schedule.get_stage::<Schedule>(GoapScheduleLabel).is_some()
+        || schedule
+            .get_stage::<SystemStage>(GoapStage::Actions)
+            .is_some()
+}
+
+/// The `Schedule` containing the `GoapStage`s: either the crate's own `Schedule` with
+/// `GoapSchedule::Nested`, or the `App`'s schedule.
+fn goap_schedule(schedule: &mut Schedule) -> &mut Schedule {
+    if schedule.get_stage::<Schedule>(GoapScheduleLabel).is_some() {
+        return schedule
+            .get_stage_mut::<Schedule>(GoapScheduleLabel)
+            .unwrap();
+    }
+
+    assert!(
+        has_goap_stages(schedule),
+        "The GoapStages are not part of the App's schedule, e.g. with GoapSchedule::Manual. Use GoapScheduleExt on the Schedule created by GoapPlugin::create_schedule instead"
+    );
+
+    schedule
+}
+
+impl GoapAppExt for App {
+    fn add_goap_system<Params>(
+        &mut self,
+        stage: GoapStage,
+        system: impl IntoSystemDescriptor<Params>,
+    ) -> &mut Self {
+        goap_schedule(&mut self.schedule).add_goap_system(stage, system);
+        self
+    }
+
+    fn add_goap_system_set(&mut self, stage: GoapStage, system_set: SystemSet) -> &mut Self {
+        goap_schedule(&mut self.schedule).add_goap_system_set(stage, system_set);
+        self
+    }
+
+    fn add_goap_action<T: GoapAction>(&mut self) -> &mut Self {
+        goap_schedule(&mut self.schedule).add_goap_action::<T>();
+        self
+    }
+
+    fn add_goap_sensor<T: Sensor>(&mut self) -> &mut Self {
+        goap_schedule(&mut self.schedule).add_goap_sensor::<T>();
+        self
+    }
+
+    #[cfg(feature = "serde")]
+    fn register_goap_condition<T: Condition + 'static>(
+        &mut self,
+    ) -> Result<&mut Self, ConditionNameConflict> {
+        register_condition::<T>()?;
+        Ok(self)
+    }
+
+    #[cfg(feature = "assets")]
+    fn register_goap_marker(
+        &mut self,
+        name: impl Into<String>,
+        marker_component: impl MarkerComponent + 'static,
+    ) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(GoapMarkerRegistry::default)
+            .register(name, marker_component);
+        self
+    }
+
+    fn set_goap_run_criteria<P>(
+        &mut self,
+        run_criteria: impl IntoSystem<(), ShouldRun, P>,
+    ) -> &mut Self {
+        self.schedule
+            .get_stage_mut::<Schedule>(GoapScheduleLabel)
+            .expect("The GoapPlugin must be added with GoapSchedule::Nested to set a run criteria")
+            .set_run_criteria(run_criteria);
+        self
+    }
+}
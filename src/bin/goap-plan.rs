@@ -0,0 +1,216 @@
+//! Plans for the `Actor`s of a `GoapDomain` file without launching the game, e.g. to sanity-check
+//! domain files in CI.
+//!
+//! ```text
+//! goap-plan <DOMAIN> [--actor <NAME>] [--dijkstra] [--max-nodes <N>] [--explain]
+//! ```
+//!
+//! Exits with 1 if any `Actor` has no plan, and with 2 if the arguments or the domain are invalid.
+
+use std::{
+    env, fmt, fs,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use bevy_goap::{
+    planner::{self, PlannerStrategy, SearchOptions},
+    DomainProblem, GoapDomain,
+};
+
+const USAGE: &str =
+    "Usage: goap-plan <DOMAIN> [--actor <NAME>] [--dijkstra] [--max-nodes <N>] [--explain]";
+
+struct Args {
+    path: String,
+    actor: Option<String>,
+    options: SearchOptions,
+    explain: bool,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut path = None;
+        let mut actor = None;
+        let mut options = SearchOptions::default();
+        let mut explain = false;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--actor" => actor = Some(args.next().ok_or("--actor requires a name")?),
+                "--dijkstra" => options.strategy = PlannerStrategy::Dijkstra,
+                "--max-nodes" => {
+                    let max_nodes = args.next().ok_or("--max-nodes requires a number")?;
+                    options.max_expanded_nodes = Some(
+                        max_nodes
+                            .parse()
+                            .map_err(|_| format!("invalid --max-nodes {max_nodes:?}"))?,
+                    );
+                }
+                "--explain" => explain = true,
+                _ if arg.starts_with("--") => return Err(format!("unknown option {arg}")),
+                _ if path.is_none() => path = Some(arg),
+                _ => return Err(format!("unexpected argument {arg:?}")),
+            }
+        }
+
+        Ok(Self {
+            path: path.ok_or("missing domain file")?,
+            actor,
+            options,
+            explain,
+        })
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("error: {error}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Plans for the selected `Actor`s, returning whether every `Actor` has a plan.
+fn run(args: &Args) -> Result<bool, String> {
+    let contents =
+        fs::read_to_string(&args.path).map_err(|error| format!("{}: {error}", args.path))?;
+
+    let domain = if args.path.ends_with(".json") {
+        GoapDomain::from_json(&contents).map_err(|error| error.to_string())
+    } else {
+        GoapDomain::from_ron(&contents).map_err(|error| error.to_string())
+    }
+    .map_err(|error| format!("{}: {error}", args.path))?;
+
+    let actors = match &args.actor {
+        Some(actor) => vec![actor.as_str()],
+        None => domain
+            .actors
+            .iter()
+            .map(|template| template.name.as_str())
+            .collect(),
+    };
+
+    let mut failed = 0;
+    let mut total_time = Duration::ZERO;
+
+    for actor in actors.iter() {
+        let problem = domain.problem(actor).map_err(|error| error.to_string())?;
+
+        let started = Instant::now();
+        let result = planner::search(
+            &problem.start,
+            &problem.goal,
+            &problem.actions,
+            &args.options,
+            |_, _| {},
+        );
+        let elapsed = started.elapsed();
+        total_time += elapsed;
+
+        match &result.plan {
+            Some(plan) => {
+                println!(
+                    "{actor}: plan with cost {} ({} actions)",
+                    plan.cost,
+                    plan.actions.len()
+                );
+
+                for (index, action_name) in plan.actions.iter().enumerate() {
+                    let cost = problem
+                        .actions
+                        .iter()
+                        .find(|action| action.id == *action_name)
+                        .map_or(0, |action| action.cost);
+
+                    println!("  {}. {action_name} (cost {cost})", index + 1);
+                }
+            }
+            None if problem.start.satisfies(&problem.goal) => {
+                println!("{actor}: goal already met");
+            }
+            None => {
+                failed += 1;
+                println!("{actor}: no plan found");
+
+                if args.explain {
+                    print_explanation(&problem);
+                }
+            }
+        }
+
+        println!("  expanded {} nodes in {elapsed:?}", result.expanded_nodes);
+    }
+
+    println!(
+        "planned for {} actors in {total_time:?}, {failed} without a plan",
+        actors.len()
+    );
+
+    if failed > 0 && !args.explain {
+        println!("run with --explain to find out why");
+    }
+
+    Ok(failed == 0)
+}
+
+fn print_explanation(problem: &DomainProblem) {
+    let explanation = planner::explain(&problem.start, &problem.goal, &problem.actions);
+
+    for (condition, value) in explanation.unproducible_goals.iter() {
+        println!(
+            "  goal {} is not met initially, and is not a postcondition of any action",
+            Fact(condition, *value)
+        );
+    }
+
+    for (condition, value) in explanation.unreachable_goals.iter() {
+        println!("  goal {} cannot be reached", Fact(condition, *value));
+    }
+
+    for (action_name, preconditions) in explanation.unreachable_preconditions.iter() {
+        let preconditions = preconditions
+            .iter()
+            .map(|(condition, value)| Fact(condition, *value).to_string())
+            .collect::<Vec<_>>();
+
+        println!(
+            "  action {action_name} can never be chosen, as {} cannot be reached",
+            preconditions.join(", ")
+        );
+    }
+
+    if explanation.is_empty() {
+        println!("  the goal is reachable when ignoring that actions undo each other's postconditions, so either they conflict, or --max-nodes was exceeded");
+    }
+
+    let mut initial_state = problem
+        .start
+        .iter()
+        .map(|(condition, value)| Fact(condition, value).to_string())
+        .collect::<Vec<_>>();
+    initial_state.sort();
+
+    println!("  initial state: {}", initial_state.join(", "));
+}
+
+/// Formats a `Condition` with its value, e.g. `has_axe = true`.
+struct Fact<'a>(&'a str, bool);
+
+impl fmt::Display for Fact<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.0, self.1)
+    }
+}
@@ -0,0 +1,93 @@
+use std::any::{type_name, Any, TypeId};
+
+use bevy::{prelude::Component, utils::HashMap};
+
+/// A key of a `Blackboard`, with the type of the value stored under it, e.g. a `TargetTree` key
+/// with an `Entity` value.
+pub trait BlackboardKey: 'static {
+    type Value: Send + Sync + 'static;
+}
+
+/// Data shared between the `Action`s of an `Actor`, e.g. a "find nearest tree" `Action` storing the
+/// tree it found for the "walk to" and "chop" `Action`s that follow it in the plan. Every `Actor`
+/// has a `Blackboard`, on the `Actor` entity.
+///
+/// Values are keyed by a `BlackboardKey` type and kept until they are removed, across plans, so an
+/// `Action` may also leave a hint during evaluation for when it is executed. The crate never reads
+/// or clears a `Blackboard`.
+///
+/// Values are stored type-erased, so a `Blackboard` does not implement `Reflect` and is not
+/// included in an `ActorSnapshot` or a scene. Keep values that must be saved in components.
+#[derive(Component, Default)]
+pub struct Blackboard {
+    values: HashMap<TypeId, BlackboardValue>,
+}
+
+struct BlackboardValue {
+    key_name: &'static str,
+    value: Box<dyn Any + Send + Sync>,
+}
+
+impl Blackboard {
+    /// Stores `value` under the key `K`, returning the previous value.
+    pub fn insert<K: BlackboardKey>(&mut self, _key: K, value: K::Value) -> Option<K::Value> {
+        self.values
+            .insert(
+                TypeId::of::<K>(),
+                BlackboardValue {
+                    key_name: type_name::<K>(),
+                    value: Box::new(value),
+                },
+            )
+            .and_then(|previous| previous.value.downcast().ok())
+            .map(|previous| *previous)
+    }
+
+    /// The value stored under the key `K`, if any.
+    pub fn get<K: BlackboardKey>(&self, _key: K) -> Option<&K::Value> {
+        self.values
+            .get(&TypeId::of::<K>())
+            .and_then(|entry| entry.value.downcast_ref())
+    }
+
+    /// A mutable reference to the value stored under the key `K`, if any.
+    pub fn get_mut<K: BlackboardKey>(&mut self, _key: K) -> Option<&mut K::Value> {
+        self.values
+            .get_mut(&TypeId::of::<K>())
+            .and_then(|entry| entry.value.downcast_mut())
+    }
+
+    /// Removes the value stored under the key `K`, returning it.
+    pub fn remove<K: BlackboardKey>(&mut self, _key: K) -> Option<K::Value> {
+        self.values
+            .remove(&TypeId::of::<K>())
+            .and_then(|entry| entry.value.downcast().ok())
+            .map(|value| *value)
+    }
+
+    /// Whether a value is stored under the key `K`.
+    pub fn contains<K: BlackboardKey>(&self, _key: K) -> bool {
+        self.values.contains_key(&TypeId::of::<K>())
+    }
+
+    /// Removes every stored value.
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    /// Whether no value is stored.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The names of the keys with a stored value, for diagnostics.
+    pub fn keys(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.values.values().map(|entry| entry.key_name)
+    }
+}
+
+impl std::fmt::Debug for Blackboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.keys()).finish()
+    }
+}
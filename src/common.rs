@@ -2,6 +2,9 @@ use bevy::prelude::{Commands, Component, Entity};
 
 pub trait MarkerComponent: Send + Sync {
     fn insert(&self, commands: &mut Commands, entity_to_insert_to: Entity);
+
+    /// The type name of the marker component, used to name what it marks.
+    fn name(&self) -> &'static str;
 }
 
 impl<T> MarkerComponent for T
@@ -11,4 +14,49 @@ where
     fn insert(&self, commands: &mut Commands, entity_to_insert_to: Entity) {
         commands.entity(entity_to_insert_to).insert(T::clone(self));
     }
+
+    fn name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+}
+
+/// A name deserialized with `deserialize_name`. Spelling out `&'static str` on a field would make
+/// serde borrow it from the deserializer instead.
+pub(crate) type InternedName = &'static str;
+
+/// Returns a `&'static str` equal to `name`, leaking each distinct name only once.
+#[cfg(feature = "serde")]
+pub(crate) fn intern(name: String) -> &'static str {
+    use std::{
+        collections::HashSet,
+        sync::{Mutex, OnceLock},
+    };
+
+    static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+    let mut names = NAMES.get_or_init(Default::default).lock().unwrap();
+
+    match names.get(name.as_str()) {
+        Some(interned) => interned,
+        None => {
+            let interned: &'static str = Box::leak(name.into_boxed_str());
+            names.insert(interned);
+            interned
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_name<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<&'static str, D::Error> {
+    <String as serde::Deserialize>::deserialize(deserializer).map(intern)
+}
+
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_names<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<&'static str>, D::Error> {
+    <Vec<String> as serde::Deserialize>::deserialize(deserializer)
+        .map(|names| names.into_iter().map(intern).collect())
 }
@@ -0,0 +1,420 @@
+use std::{collections::VecDeque, marker::PhantomData, sync::Arc};
+
+use bevy::{
+    log::{debug, info},
+    prelude::{BuildChildren, Commands, Component, Entity, Query, Res},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    action::{Action, ActionBuilder, ActionState, ActionStateQuery, BuildAction, EvaluationResult},
+    actor::{Actor, Plan},
+    common::MarkerComponent,
+    events::LifecycleEvents,
+    planner,
+    settings::{GoapLogLevel, GoapLogSettings, PlannerSettings},
+    state::GoapState,
+    ActionStarted, Condition,
+};
+
+/// An `Action` that decomposes into a sub-plan of other `Action`s once it is started, e.g. "build
+/// house" into "gather wood", "lay foundation" and "raise walls".
+///
+/// The planner treats a `CompoundAction` like any other `Action`, using its preconditions,
+/// postconditions and cost. The crate drives every `ActionState` of a `CompoundAction`, so no
+/// `Action` system is needed for its marker component: it always evaluates successfully, and once
+/// it reaches `ActionState::Started`, the crate creates its sub-plan and stays in
+/// `ActionState::Executing` until every sub-action has completed. The sub-actions belong to the
+/// same `Actor`, so they are handled by the same `Action` systems as any other `Action`.
+///
+/// If the sub-plan cannot be created, or a sub-action fails, the `CompoundAction` fails, and so
+/// does the plan of the `Actor`.
+#[derive(Component, Clone, Debug)]
+pub struct CompoundAction {
+    pub(crate) decomposition: Decomposition,
+    pub(crate) sub_actions: Vec<Entity>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Decomposition {
+    Sequence,
+    SubGoal(GoapState),
+}
+
+impl CompoundAction {
+    /// Builds a `CompoundAction` that executes its sub-actions in the order they are added. It
+    /// fails when started if a sub-action's preconditions are not met by those before it.
+    pub fn sequence(marker_component: impl MarkerComponent + 'static) -> SequenceBuilder {
+        CompoundActionBuilder::new(marker_component, Decomposition::Sequence)
+    }
+
+    /// Builds a `CompoundAction` that plans from the current state of the `Actor` to a sub-goal
+    /// when it is started, using its sub-actions. Sub-actions are not evaluated, so they are
+    /// planned with their current cost.
+    pub fn sub_goal(marker_component: impl MarkerComponent + 'static) -> SubGoalBuilder {
+        CompoundActionBuilder::new(marker_component, Decomposition::SubGoal(GoapState::new()))
+    }
+
+    /// The sub-action entities of this `CompoundAction`, in the order they were added.
+    pub fn sub_actions(&self) -> &[Entity] {
+        &self.sub_actions
+    }
+
+    /// Creates the sub-plan from `current_state`, or returns `None` if the sub-goal cannot be
+    /// reached or a sub-action of a sequence would be started with unmet preconditions.
+    fn decompose(
+        &self,
+        current_state: &GoapState,
+        actions: &Query<&Action>,
+        planner_settings: &PlannerSettings,
+    ) -> Option<Plan> {
+        match &self.decomposition {
+            Decomposition::Sequence => {
+                let mut expected_state = current_state.clone();
+                let mut cost = 0;
+
+                for action_entity in &self.sub_actions {
+                    let action = actions.get(*action_entity).ok()?;
+
+                    if !expected_state.state.satisfies(&action.preconditions.state) {
+                        return None;
+                    }
+
+                    expected_state.extend(action.postconditions.clone());
+                    cost += action.cost;
+                }
+
+                Some(Plan {
+                    steps: self.sub_actions.clone(),
+                    cost,
+                    expected_state,
+                })
+            }
+            Decomposition::SubGoal(goal) if current_state.state.satisfies(&goal.state) => {
+                Some(Plan {
+                    steps: vec![],
+                    cost: 0,
+                    expected_state: current_state.clone(),
+                })
+            }
+            Decomposition::SubGoal(goal) => {
+                let planner_actions = self
+                    .sub_actions
+                    .iter()
+                    .filter_map(|action_entity| {
+                        Some(
+                            actions
+                                .get(*action_entity)
+                                .ok()?
+                                .planner_action(*action_entity),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                planner::search(
+                    &current_state.state,
+                    &goal.state,
+                    &planner_actions,
+                    &planner_settings.search_options(),
+                    |_, _| {},
+                )
+                .plan
+                .map(|plan| Plan {
+                    steps: plan.actions,
+                    cost: plan.cost,
+                    expected_state: GoapState {
+                        state: plan.end_state,
+                    },
+                })
+            }
+        }
+    }
+}
+
+/// Marks a `CompoundActionBuilder` created by `CompoundAction::sequence`.
+#[derive(Clone, Copy, Debug)]
+pub enum SequenceDecomposition {}
+
+/// Marks a `CompoundActionBuilder` created by `CompoundAction::sub_goal`.
+#[derive(Clone, Copy, Debug)]
+pub enum SubGoalDecomposition {}
+
+/// Builds a `CompoundAction` that executes its sub-actions in order.
+pub type SequenceBuilder = CompoundActionBuilder<SequenceDecomposition>;
+
+/// Builds a `CompoundAction` that plans to its sub-goal.
+pub type SubGoalBuilder = CompoundActionBuilder<SubGoalDecomposition>;
+
+/// Builds a `CompoundAction`. `D` is the kind of decomposition, so that only a `SubGoalBuilder` can
+/// set a sub-goal.
+pub struct CompoundActionBuilder<D> {
+    action: ActionBuilder,
+    decomposition: Decomposition,
+    sub_actions: Vec<Arc<dyn BuildAction>>,
+    kind: PhantomData<fn() -> D>,
+}
+
+impl<D> Clone for CompoundActionBuilder<D> {
+    fn clone(&self) -> Self {
+        Self {
+            action: self.action.clone(),
+            decomposition: self.decomposition.clone(),
+            sub_actions: self.sub_actions.clone(),
+            kind: PhantomData,
+        }
+    }
+}
+
+impl<D> CompoundActionBuilder<D> {
+    fn new(marker_component: impl MarkerComponent + 'static, decomposition: Decomposition) -> Self {
+        Self {
+            action: Action::build(marker_component),
+            decomposition,
+            sub_actions: vec![],
+            kind: PhantomData,
+        }
+    }
+
+    pub fn with_precondition<T: Condition + 'static>(
+        mut self,
+        precondition: T,
+        value: bool,
+    ) -> Self {
+        self.action = self.action.with_precondition(precondition, value);
+        self
+    }
+
+    pub fn with_postcondition<T: Condition + 'static>(
+        mut self,
+        postcondition: T,
+        value: bool,
+    ) -> Self {
+        self.action = self.action.with_postcondition(postcondition, value);
+        self
+    }
+
+    /// Sets the cost of this `CompoundAction` for the planner. Defaults to 1.
+    pub fn with_cost(mut self, cost: u32) -> Self {
+        self.action = self.action.with_cost(cost);
+        self
+    }
+
+    /// Adds a sub-action, which may itself be a `CompoundAction`.
+    pub fn with_sub_action(mut self, sub_action: impl BuildAction + 'static) -> Self {
+        self.sub_actions.push(Arc::new(sub_action));
+        self
+    }
+}
+
+impl SubGoalBuilder {
+    /// Sets the value of a `Condition` in the sub-goal.
+    pub fn with_sub_goal<T: Condition + 'static>(mut self, _condition: T, value: bool) -> Self {
+        if let Decomposition::SubGoal(goal) = &mut self.decomposition {
+            goal.insert::<T>(value);
+        }
+
+        self
+    }
+}
+
+impl<D> BuildAction for CompoundActionBuilder<D> {
+    fn build(&self, commands: &mut Commands, actor_entity: Entity) -> Entity {
+        let action_entity = self.action.build(commands, actor_entity);
+
+        let sub_actions = self
+            .sub_actions
+            .iter()
+            .map(|sub_action| sub_action.build(commands, actor_entity))
+            .collect::<Vec<_>>();
+
+        // Sub-actions are children of the CompoundAction, so that they are despawned with it.
+        commands
+            .entity(action_entity)
+            .push_children(&sub_actions)
+            .insert(CompoundAction {
+                decomposition: self.decomposition.clone(),
+                sub_actions,
+            });
+
+        action_entity
+    }
+
+    fn name(&self) -> &'static str {
+        self.action.name()
+    }
+
+    fn preconditions(&self) -> &GoapState {
+        self.action.preconditions()
+    }
+
+    fn postconditions(&self) -> &GoapState {
+        self.action.postconditions()
+    }
+}
+
+/// The sub-plan of a `CompoundAction` that is being executed by an `Actor`, see `Actor::sub_plans`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SubPlan {
+    pub(crate) compound_action: Entity,
+    pub(crate) plan: Plan,
+    pub(crate) path: VecDeque<Entity>,
+}
+
+impl SubPlan {
+    fn new(compound_action: Entity, plan: Plan) -> Self {
+        Self {
+            compound_action,
+            path: plan.steps.iter().copied().collect(),
+            plan,
+        }
+    }
+
+    /// The `CompoundAction` entity this sub-plan was created for.
+    pub fn compound_action(&self) -> Entity {
+        self.compound_action
+    }
+
+    /// The sub-action entities of this sub-plan, in the order they are executed.
+    pub fn steps(&self) -> &[Entity] {
+        &self.plan.steps
+    }
+
+    /// The index into `steps` of the sub-action currently being executed, or `None` once every
+    /// sub-action has completed.
+    pub fn current_step(&self) -> Option<usize> {
+        (!self.path.is_empty()).then(|| self.plan.steps.len() - self.path.len())
+    }
+
+    /// The total cost of the sub-actions in this sub-plan.
+    pub fn cost(&self) -> i32 {
+        self.plan.cost
+    }
+}
+
+/// Stops every sub-plan of `actor`, transitioning its remaining sub-actions and its
+/// `CompoundAction`s to `ActionState::Idle`. Returns the `CompoundAction` entities, from the
+/// innermost to the outermost.
+pub(crate) fn cancel_sub_plans(
+    actor: &mut Actor,
+    action_states: &mut Query<ActionStateQuery>,
+) -> Vec<Entity> {
+    let mut compound_actions = vec![];
+
+    while let Some(sub_plan) = actor.sub_plans.pop() {
+        for action_entity in sub_plan
+            .path
+            .iter()
+            .chain(std::iter::once(&sub_plan.compound_action))
+        {
+            if let Ok(mut action_state) = action_states.get_mut(*action_entity) {
+                action_state.issue(ActionState::Idle);
+            }
+        }
+
+        compound_actions.push(sub_plan.compound_action);
+    }
+
+    compound_actions
+}
+
+/// Drives the `ActionState` of every `CompoundAction`: creating the sub-plan of each started
+/// `CompoundAction`, and completing each `CompoundAction` whose sub-plan has completed.
+#[allow(clippy::too_many_arguments)]
+pub fn compound_action_system(
+    compound_actions: Query<(Entity, &CompoundAction)>,
+    actions: Query<&Action>,
+    mut actors: Query<&mut Actor>,
+    mut action_states: Query<ActionStateQuery>,
+    mut events: LifecycleEvents,
+    planner_settings: Res<PlannerSettings>,
+    log: Res<GoapLogSettings>,
+) {
+    for (compound_entity, compound_action) in compound_actions.iter() {
+        let Ok(action) = actions.get(compound_entity) else {
+            continue;
+        };
+        let Ok(mut actor) = actors.get_mut(action.actor_entity) else {
+            continue;
+        };
+
+        match action_states.get(compound_entity).map(|state| state.get()) {
+            Ok(ActionState::Evaluate) => {
+                action_states
+                    .get_mut(compound_entity)
+                    .unwrap()
+                    .issue(ActionState::EvaluationComplete(EvaluationResult::Success));
+            }
+            Ok(ActionState::NotInPlan(_)) => {
+                action_states
+                    .get_mut(compound_entity)
+                    .unwrap()
+                    .issue(ActionState::Idle);
+            }
+            Ok(ActionState::Started) => {
+                let Some(plan) =
+                    compound_action.decompose(&actor.current_state, &actions, &planner_settings)
+                else {
+                    if log.actions >= GoapLogLevel::Info {
+                        info!(actor = ?action.actor_entity, action = ?compound_entity, "No sub-plan available");
+                    }
+
+                    action_states
+                        .get_mut(compound_entity)
+                        .unwrap()
+                        .issue(ActionState::Failure);
+                    continue;
+                };
+
+                if log.actions >= GoapLogLevel::Verbose {
+                    debug!(actor = ?action.actor_entity, action = ?compound_entity, steps = plan.steps.len(), "Sub-plan created");
+                }
+
+                let sub_plan = SubPlan::new(compound_entity, plan);
+
+                for (index, action_entity) in sub_plan.path.iter().enumerate() {
+                    if let Ok(mut action_state) = action_states.get_mut(*action_entity) {
+                        action_state.issue(if index == 0 {
+                            ActionState::Started
+                        } else {
+                            ActionState::WaitingToStart
+                        });
+                    }
+                }
+
+                if let Some(first_action_entity) = sub_plan.path.front() {
+                    events.action_started.send(ActionStarted {
+                        actor_entity: action.actor_entity,
+                        action_entity: *first_action_entity,
+                    });
+                }
+
+                actor.sub_plans.push(sub_plan);
+
+                action_states
+                    .get_mut(compound_entity)
+                    .unwrap()
+                    .issue(ActionState::Executing);
+            }
+            Ok(ActionState::Executing) => {
+                let sub_plan_completed = actor.sub_plans.last().is_some_and(|sub_plan| {
+                    sub_plan.compound_action == compound_entity && sub_plan.path.is_empty()
+                });
+
+                if sub_plan_completed {
+                    actor.sub_plans.pop();
+
+                    // The Actor continues with the next Action of the enclosing plan once the
+                    // CompoundAction completes.
+                    action_states
+                        .get_mut(compound_entity)
+                        .unwrap()
+                        .issue(ActionState::Complete);
+                }
+            }
+            _ => {}
+        }
+    }
+}
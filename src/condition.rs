@@ -1 +1,14 @@
-pub trait Condition {}
+pub trait Condition {
+    /// The name of this `Condition`, as shown in diagnostics and used to identify it in serialized
+    /// states. Defaults to the full type name.
+    ///
+    /// The output of `std::any::type_name` may change between compiler versions and when the type
+    /// is moved to another module, so set an explicit name, e.g. with `#[condition(name =
+    /// "HasAxe")]`, for `Condition`s stored in saved states.
+    fn name() -> &'static str
+    where
+        Self: Sized,
+    {
+        std::any::type_name::<Self>()
+    }
+}
@@ -0,0 +1,388 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use bevy::{
+    asset::{AssetLoader, BoxedFuture, Error, LoadContext, LoadedAsset},
+    log::{info, warn},
+    prelude::{
+        AddAsset, App, AssetEvent, Assets, ChangeTrackers, Commands, Component,
+        DespawnRecursiveExt, Entity, EventReader, Handle, Plugin, Query, Res, Resource,
+    },
+    utils::{HashMap, HashSet},
+};
+
+use super::{ActorTemplate, DomainError, GoapDomain};
+use crate::{
+    action::{Action, ActionBuilder, ActionState, ActionStateQuery, BuildAction},
+    actor::{Actor, ActorBuilder, ActorState},
+    app::has_goap_stages,
+    common::{intern, MarkerComponent},
+    compound::cancel_sub_plans,
+    planning::PlanningState,
+    settings::{GoapLogLevel, GoapLogSettings},
+    state::{ConditionId, GoapState},
+    GoapAppExt, GoapStage,
+};
+
+impl GoapDomain {
+    /// Creates an `ActorBuilder` from the `ActorTemplate` named `name`, resolving marker components
+    /// with `markers`.
+    pub fn actor(
+        &self,
+        name: &str,
+        markers: &GoapMarkerRegistry,
+    ) -> Result<ActorBuilder, DomainError> {
+        let template = self.template(name)?;
+
+        let actions = self
+            .action_builders(template, markers)?
+            .into_iter()
+            .map(|action| Arc::new(action) as Arc<dyn BuildAction>)
+            .collect();
+
+        Ok(ActorBuilder {
+            marker_component: markers.get(&template.marker)?,
+            actions,
+            initial_state: self.state(&template.initial_state)?,
+            initial_goal: self.state(&template.goal)?,
+        })
+    }
+
+    fn action_builders(
+        &self,
+        template: &ActorTemplate,
+        markers: &GoapMarkerRegistry,
+    ) -> Result<Vec<DomainActionBuilder>, DomainError> {
+        self.definitions(template)?
+            .into_iter()
+            .map(|definition| {
+                Ok(DomainActionBuilder {
+                    name: definition.name.clone(),
+                    builder: ActionBuilder {
+                        marker_component: markers.get(&definition.marker)?,
+                        preconditions: self.state(&definition.preconditions)?,
+                        postconditions: self.state(&definition.postconditions)?,
+                        cost: definition.cost as i32,
+                        timeout: None,
+                        channels: definition
+                            .channels
+                            .iter()
+                            .map(|channel| intern(channel.clone()))
+                            .collect(),
+                    },
+                })
+            })
+            .collect()
+    }
+
+    fn state(&self, values: &BTreeMap<String, bool>) -> Result<GoapState, DomainError> {
+        let mut state = GoapState::new();
+
+        for (name, value) in self.condition_state(values)?.iter() {
+            state.insert_id(ConditionId::named(name), value);
+        }
+
+        Ok(state)
+    }
+}
+
+/// The marker components that a `GoapDomain` can refer to, by name. Register them with
+/// `GoapAppExt::register_goap_marker`.
+#[derive(Resource, Clone, Default)]
+pub struct GoapMarkerRegistry {
+    markers: HashMap<String, Arc<dyn MarkerComponent>>,
+}
+
+impl GoapMarkerRegistry {
+    /// Registers `marker_component` as `name`, replacing any marker component previously registered
+    /// with that name.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        marker_component: impl MarkerComponent + 'static,
+    ) {
+        self.markers.insert(name.into(), Arc::new(marker_component));
+    }
+
+    /// Whether a marker component is registered as `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.markers.contains_key(name)
+    }
+
+    fn get(&self, name: &str) -> Result<Arc<dyn MarkerComponent>, DomainError> {
+        self.markers
+            .get(name)
+            .cloned()
+            .ok_or_else(|| DomainError::UnknownMarker(name.to_string()))
+    }
+}
+
+/// Builds an `Action` of a `GoapDomain`, marking it with its `DomainAction`.
+struct DomainActionBuilder {
+    name: String,
+    builder: ActionBuilder,
+}
+
+impl BuildAction for DomainActionBuilder {
+    fn build(&self, commands: &mut Commands, actor_entity: Entity) -> Entity {
+        let action_entity = self.builder.build(commands, actor_entity);
+
+        commands.entity(action_entity).insert(DomainAction {
+            name: self.name.clone(),
+        });
+
+        action_entity
+    }
+
+    fn name(&self) -> &'static str {
+        self.builder.name()
+    }
+
+    fn preconditions(&self) -> &GoapState {
+        self.builder.preconditions()
+    }
+
+    fn postconditions(&self) -> &GoapState {
+        self.builder.postconditions()
+    }
+}
+
+/// Spawns an `Actor` from the `ActorTemplate` named `template` once the `domain` is loaded, and
+/// keeps it up to date when the `domain` is modified, e.g. while the game runs.
+///
+/// When the `domain` is modified, the conditions, costs and channels of existing `Action`s are
+/// updated, `Action`s are added or removed to match the `ActorTemplate`, and the `Actor` stops its
+/// current plan and transitions to `ActorState::RequiresPlan`. The state and goal of the `Actor`
+/// are kept.
+#[derive(Component, Clone, Debug)]
+pub struct DomainActor {
+    pub domain: Handle<GoapDomain>,
+    pub template: String,
+}
+
+impl DomainActor {
+    pub fn new(domain: Handle<GoapDomain>, template: impl Into<String>) -> Self {
+        Self {
+            domain,
+            template: template.into(),
+        }
+    }
+}
+
+/// The name of the `ActionDefinition` an `Action` was built from.
+#[derive(Component, Clone, Debug)]
+pub struct DomainAction {
+    pub name: String,
+}
+
+/// Loads `GoapDomain`s from `.goap.ron` and `.goap.json` files.
+#[derive(Default)]
+pub struct GoapDomainLoader;
+
+impl AssetLoader for GoapDomainLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            let domain = match load_context.path().extension() {
+                Some(extension) if extension == "json" => serde_json::from_slice(bytes)?,
+                _ => ron::de::from_bytes(bytes)?,
+            };
+
+            load_context.set_default_asset(LoadedAsset::<GoapDomain>::new(domain));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["goap.ron", "goap.json"]
+    }
+}
+
+/// Adds the `GoapDomain` asset and its loader, and keeps `DomainActor`s up to date in
+/// `GoapStage::Actors`. Requires Bevy's `AssetPlugin`, with `watch_for_changes` enabled to reload
+/// modified domain files while the game runs.
+///
+/// Add it after the `GoapPlugin`. With `GoapSchedule::Manual`, also call
+/// `GoapScheduleExt::add_goap_domain_actors` on the crate's `Schedule`.
+pub struct GoapAssetPlugin;
+
+impl Plugin for GoapAssetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<GoapDomain>()
+            .init_asset_loader::<GoapDomainLoader>()
+            .init_resource::<GoapMarkerRegistry>()
+            .init_resource::<GoapLogSettings>();
+
+        if has_goap_stages(&app.schedule) {
+            app.add_goap_system(GoapStage::Actors, domain_actor_system);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn domain_actor_system(
+    mut commands: Commands,
+    mut ev_asset: EventReader<AssetEvent<GoapDomain>>,
+    domains: Res<Assets<GoapDomain>>,
+    markers: Res<GoapMarkerRegistry>,
+    mut actors: Query<(
+        Entity,
+        &DomainActor,
+        ChangeTrackers<DomainActor>,
+        Option<&mut Actor>,
+        Option<&mut ActorState>,
+    )>,
+    mut actions: Query<(Option<&DomainAction>, &mut Action)>,
+    mut action_states: Query<ActionStateQuery>,
+    mut planning_states: Query<&mut PlanningState>,
+    log: Res<GoapLogSettings>,
+) {
+    let mut created = HashSet::new();
+    let mut modified = HashSet::new();
+
+    for event in ev_asset.iter() {
+        match event {
+            AssetEvent::Created { handle } => {
+                created.insert(handle.clone_weak());
+            }
+            AssetEvent::Modified { handle } => {
+                modified.insert(handle.clone_weak());
+            }
+            AssetEvent::Removed { .. } => {}
+        }
+    }
+
+    if !modified.is_empty() {
+        // Cached plans may have been created with the previous conditions of the modified Actions.
+        for mut planning_state in planning_states.iter_mut() {
+            planning_state.clear_cache();
+        }
+    }
+
+    for (actor_entity, domain_actor, trackers, actor, actor_state) in actors.iter_mut() {
+        let Some(domain) = domains.get(&domain_actor.domain) else {
+            continue;
+        };
+
+        let result = match (actor, actor_state) {
+            (Some(mut actor), Some(mut actor_state)) => {
+                if !modified.contains(&domain_actor.domain) {
+                    continue;
+                }
+
+                reload_actor(
+                    &mut commands,
+                    actor_entity,
+                    &mut actor,
+                    domain,
+                    domain_actor,
+                    &markers,
+                    &mut actions,
+                    &mut action_states,
+                )
+                .map(|()| {
+                    *actor_state = ActorState::RequiresPlan;
+
+                    if log.actors >= GoapLogLevel::Info {
+                        info!(actor = ?actor_entity, template = domain_actor.template, "Domain reloaded");
+                    }
+                })
+            }
+            _ => {
+                if !trackers.is_added()
+                    && !created.contains(&domain_actor.domain)
+                    && !modified.contains(&domain_actor.domain)
+                {
+                    continue;
+                }
+
+                domain
+                    .actor(&domain_actor.template, &markers)
+                    .map(|actor_builder| {
+                        commands.entity(actor_entity).insert(actor_builder);
+                    })
+            }
+        };
+
+        // Logged regardless of `GoapLogSettings`, as the `Actor` is not built or updated.
+        if let Err(error) = result {
+            warn!(actor = ?actor_entity, %error, "Invalid domain");
+        }
+    }
+}
+
+/// Updates the `Action`s of an `Actor` to match its `ActorTemplate`, and stops its current plan,
+/// which may refer to removed `Action`s.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn reload_actor(
+    commands: &mut Commands,
+    actor_entity: Entity,
+    actor: &mut Actor,
+    domain: &GoapDomain,
+    domain_actor: &DomainActor,
+    markers: &GoapMarkerRegistry,
+    actions: &mut Query<(Option<&DomainAction>, &mut Action)>,
+    action_states: &mut Query<ActionStateQuery>,
+) -> Result<(), DomainError> {
+    let template = domain.template(&domain_actor.template)?;
+
+    // Resolve every Action first, so that an invalid domain leaves the Actor untouched.
+    let action_builders = domain.action_builders(template, markers)?;
+
+    let mut action_entities = vec![];
+
+    for action_builder in action_builders {
+        let existing = actor.actions.iter().copied().find(|action_entity| {
+            !action_entities.contains(action_entity)
+                && actions
+                    .get(*action_entity)
+                    .is_ok_and(|(domain_action, action)| {
+                        domain_action
+                            .is_some_and(|domain_action| domain_action.name == action_builder.name)
+                            && action.name == action_builder.name()
+                    })
+        });
+
+        match existing {
+            Some(action_entity) => {
+                let (_, mut action) = actions.get_mut(action_entity).unwrap();
+                action.preconditions = action_builder.builder.preconditions.clone();
+                action.postconditions = action_builder.builder.postconditions.clone();
+                action.cost = action_builder.builder.cost;
+                action.channels = action_builder.builder.channels.clone();
+
+                action_entities.push(action_entity);
+            }
+            None => {
+                action_entities.push(action_builder.build(commands, actor_entity));
+            }
+        }
+    }
+
+    for action_entity in actor.actions.iter() {
+        if action_entities.contains(action_entity) {
+            continue;
+        }
+
+        match actions.get(*action_entity) {
+            // Actions that were not built from the domain are kept.
+            Ok((None, _)) => action_entities.push(*action_entity),
+            _ => commands.entity(*action_entity).despawn_recursive(),
+        }
+    }
+
+    cancel_sub_plans(actor, action_states);
+
+    for action_entity in action_entities.iter() {
+        if let Ok(mut action_state) = action_states.get_mut(*action_entity) {
+            action_state.issue(ActionState::Idle);
+        }
+    }
+
+    actor.set_plan(None);
+    actor.actions = action_entities;
+
+    Ok(())
+}
@@ -0,0 +1,198 @@
+use std::{collections::BTreeMap, fmt};
+
+#[cfg(feature = "assets")]
+use bevy::reflect::TypeUuid;
+use serde::{Deserialize, Serialize};
+
+use crate::planner::{self, State};
+
+#[cfg(feature = "assets")]
+mod assets;
+
+#[cfg(feature = "assets")]
+pub(crate) use assets::domain_actor_system;
+#[cfg(feature = "assets")]
+pub use assets::{
+    DomainAction, DomainActor, GoapAssetPlugin, GoapDomainLoader, GoapMarkerRegistry,
+};
+
+/// The `Condition`s, `Action`s and `Actor` templates of a domain, e.g. loaded from a `.goap.ron` or
+/// `.goap.json` file by the `GoapAssetPlugin`.
+///
+/// `Condition`s are referred to by name, and must be declared in `conditions`. A `Condition` type
+/// registered with `GoapAppExt::register_goap_condition` before the domain is loaded refers to the
+/// `Condition` with its name, so that Rust code can update it. Marker components are referred to by
+/// the names they were registered with in the `GoapMarkerRegistry`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(
+    feature = "assets",
+    derive(TypeUuid),
+    uuid = "f0a1c709-16b1-4f83-920f-c9a99d139a94"
+)]
+pub struct GoapDomain {
+    #[serde(default)]
+    pub conditions: Vec<String>,
+    #[serde(default)]
+    pub actions: Vec<ActionDefinition>,
+    #[serde(default)]
+    pub actors: Vec<ActorTemplate>,
+}
+
+/// An `Action` of a `GoapDomain`, which `ActorTemplate`s refer to by its name.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ActionDefinition {
+    pub name: String,
+    /// The registered name of the marker component of the `Action`.
+    pub marker: String,
+    #[serde(default)]
+    pub preconditions: BTreeMap<String, bool>,
+    #[serde(default)]
+    pub postconditions: BTreeMap<String, bool>,
+    /// The cost of the `Action` until it is updated with `Action::update_cost`.
+    #[serde(default = "default_cost")]
+    pub cost: u32,
+    /// The resource channels the `Action` occupies while executing, see
+    /// `ActionBuilder::with_channel`.
+    #[serde(default)]
+    pub channels: Vec<String>,
+}
+
+fn default_cost() -> u32 {
+    1
+}
+
+/// An `Actor` of a `GoapDomain`, from which any number of `ActorBuilder`s can be created.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct ActorTemplate {
+    pub name: String,
+    /// The registered name of the marker component of the `Actor`.
+    pub marker: String,
+    #[serde(default)]
+    pub initial_state: BTreeMap<String, bool>,
+    #[serde(default)]
+    pub goal: BTreeMap<String, bool>,
+    /// The names of the `Action`s of the `Actor`.
+    pub actions: Vec<String>,
+}
+
+/// The reason an `ActorBuilder` could not be created from a `GoapDomain`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DomainError {
+    /// The domain has no `ActorTemplate` with this name.
+    UnknownActor(String),
+    /// An `ActorTemplate` refers to an `Action` that is not defined by the domain.
+    UnknownAction { actor: String, action: String },
+    /// No marker component is registered with this name in the `GoapMarkerRegistry`.
+    UnknownMarker(String),
+    /// A `Condition` is used without being declared in `GoapDomain::conditions`.
+    UnknownCondition(String),
+}
+
+impl fmt::Display for DomainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DomainError::UnknownActor(name) => write!(f, "no Actor named {name:?} in the domain"),
+            DomainError::UnknownAction { actor, action } => {
+                write!(
+                    f,
+                    "Actor {actor:?} refers to Action {action:?}, which is not in the domain"
+                )
+            }
+            DomainError::UnknownMarker(name) => {
+                write!(f, "no marker component registered as {name:?}")
+            }
+            DomainError::UnknownCondition(name) => {
+                write!(f, "Condition {name:?} is not declared in the domain")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DomainError {}
+
+impl GoapDomain {
+    /// Parses a domain from RON.
+    pub fn from_ron(ron: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(ron)
+    }
+
+    /// Parses a domain from JSON.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// The start state, goal and `Action`s of the `ActorTemplate` named `name`, for planning
+    /// without Bevy, e.g. with the `goap-plan` tool.
+    pub fn problem(&self, name: &str) -> Result<DomainProblem, DomainError> {
+        let template = self.template(name)?;
+
+        let actions = self
+            .definitions(template)?
+            .into_iter()
+            .map(|definition| {
+                Ok(planner::Action {
+                    id: definition.name.clone(),
+                    preconditions: self.condition_state(&definition.preconditions)?,
+                    effects: self.condition_state(&definition.postconditions)?,
+                    cost: definition.cost as i32,
+                })
+            })
+            .collect::<Result<_, DomainError>>()?;
+
+        Ok(DomainProblem {
+            start: self.condition_state(&template.initial_state)?,
+            goal: self.condition_state(&template.goal)?,
+            actions,
+        })
+    }
+
+    fn template(&self, name: &str) -> Result<&ActorTemplate, DomainError> {
+        self.actors
+            .iter()
+            .find(|template| template.name == name)
+            .ok_or_else(|| DomainError::UnknownActor(name.to_string()))
+    }
+
+    /// The `ActionDefinition` of each `Action` of `template`, in order.
+    fn definitions(&self, template: &ActorTemplate) -> Result<Vec<&ActionDefinition>, DomainError> {
+        template
+            .actions
+            .iter()
+            .map(|action_name| {
+                self.actions
+                    .iter()
+                    .find(|definition| definition.name == *action_name)
+                    .ok_or_else(|| DomainError::UnknownAction {
+                        actor: template.name.clone(),
+                        action: action_name.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// The state of `values`, checking that every `Condition` is declared in `conditions`.
+    fn condition_state(
+        &self,
+        values: &BTreeMap<String, bool>,
+    ) -> Result<State<String>, DomainError> {
+        values
+            .iter()
+            .map(|(name, value)| {
+                if self.conditions.contains(name) {
+                    Ok((name.clone(), *value))
+                } else {
+                    Err(DomainError::UnknownCondition(name.clone()))
+                }
+            })
+            .collect()
+    }
+}
+
+/// The start state, goal and `Action`s of an `ActorTemplate`, with `Condition`s and `Action`s
+/// identified by name, see `GoapDomain::problem`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DomainProblem {
+    pub start: State<String>,
+    pub goal: State<String>,
+    pub actions: Vec<planner::Action<String, String>>,
+}
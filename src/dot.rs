@@ -0,0 +1,68 @@
+use std::fmt::Write;
+
+use crate::{action::Action, state::GoapState};
+
+/// Exports a set of `Action`s as a Graphviz DOT graph. Each `Action` is a node, with an edge to
+/// every `Action` that has a precondition satisfied by one of its postconditions.
+///
+/// For the `Action`s of an `Actor`, use e.g.
+/// `domain_to_dot(actor.actions().iter().filter_map(|entity| actions.get(*entity).ok()))`, or
+/// `ActorBuilder::to_dot` before the `Actor` is built.
+pub fn domain_to_dot<'a>(actions: impl IntoIterator<Item = &'a Action>) -> String {
+    let actions = actions
+        .into_iter()
+        .map(|action| (action.name, &action.preconditions, &action.postconditions))
+        .collect::<Vec<_>>();
+
+    write_domain(&actions)
+}
+
+pub(crate) fn write_domain(actions: &[(&'static str, &GoapState, &GoapState)]) -> String {
+    let mut dot = String::from("digraph domain {\n    node [shape=box];\n");
+
+    for (index, (name, preconditions, postconditions)) in actions.iter().enumerate() {
+        let label = format!(
+            "{}\\npre: {}\\npost: {}",
+            escape(name),
+            escape(&format_state(preconditions.iter())),
+            escape(&format_state(postconditions.iter()))
+        );
+        writeln!(dot, "    a{index} [label=\"{label}\"];").unwrap();
+    }
+
+    for (from, (_, _, postconditions)) in actions.iter().enumerate() {
+        for (to, (_, preconditions, _)) in actions.iter().enumerate() {
+            let mut satisfied = postconditions
+                .iter()
+                .filter(|post| preconditions.iter().any(|pre| pre == *post))
+                .collect::<Vec<_>>();
+
+            if satisfied.is_empty() {
+                continue;
+            }
+
+            satisfied.sort();
+            writeln!(
+                dot,
+                "    a{from} -> a{to} [label=\"{}\"];",
+                escape(&format_state(satisfied.into_iter()))
+            )
+            .unwrap();
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+pub(crate) fn format_state(state: impl Iterator<Item = (&'static str, bool)>) -> String {
+    let mut state = state
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>();
+    state.sort();
+    state.join(", ")
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
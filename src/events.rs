@@ -0,0 +1,90 @@
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::{Entity, EventWriter},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Sent when a plan has been created for an `Actor`.
+#[derive(Debug, Clone)]
+pub struct PlanCreated {
+    pub actor_entity: Entity,
+    /// The `Action` entities of the plan, in the order they will be executed.
+    pub steps: Vec<Entity>,
+    /// The total cost of the `Action`s in the plan.
+    pub cost: i32,
+}
+
+/// The reason a plan failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PlanFailureReason {
+    /// The planner could not find a plan from the `Actor`'s current state to meet its goal.
+    NoPlanAvailable,
+    /// An `Action` in the plan failed during execution.
+    ActionFailed(Entity),
+    /// An `Action` in the plan exceeded its execution timeout, see `TimeoutSettings`.
+    ActionTimedOut(Entity),
+    /// The current state changed such that the remaining steps of the plan can no longer reach the
+    /// goal, see `Sensor` and `PlannerSettings::replan_on_change`.
+    PlanInvalidated,
+}
+
+/// Sent when an `Actor` could not be given a plan, or its plan failed during execution.
+#[derive(Debug, Clone)]
+pub struct PlanFailed {
+    pub actor_entity: Entity,
+    pub reason: PlanFailureReason,
+}
+
+/// Sent when an `Actor` completed every `Action` in its plan.
+#[derive(Debug, Clone)]
+pub struct PlanCompleted {
+    pub actor_entity: Entity,
+}
+
+/// Sent when an `Action` in an `Actor`'s plan is started.
+#[derive(Debug, Clone)]
+pub struct ActionStarted {
+    pub actor_entity: Entity,
+    pub action_entity: Entity,
+}
+
+/// Sent when an `Action` in an `Actor`'s plan completed successfully.
+#[derive(Debug, Clone)]
+pub struct ActionCompleted {
+    pub actor_entity: Entity,
+    pub action_entity: Entity,
+}
+
+/// Sent when an `Action` in an `Actor`'s plan failed during execution.
+#[derive(Debug, Clone)]
+pub struct ActionFailed {
+    pub actor_entity: Entity,
+    pub action_entity: Entity,
+}
+
+/// Sent when the goal of an `Actor` was changed with `Actor::update_goal` or `Actor::remove_goal`.
+#[derive(Debug, Clone)]
+pub struct GoalChanged {
+    pub actor_entity: Entity,
+}
+
+/// Sent by `action_timeout_system` when an `Action` exceeded its execution timeout, so that its
+/// failure can be told apart from other failures.
+#[derive(Debug, Clone)]
+pub(crate) struct ActionTimedOut {
+    pub(crate) action_entity: Entity,
+}
+
+/// The writers for every lifecycle event sent by the crate's systems.
+#[derive(SystemParam)]
+pub(crate) struct LifecycleEvents<'w, 's> {
+    pub(crate) plan_created: EventWriter<'w, 's, PlanCreated>,
+    pub(crate) plan_failed: EventWriter<'w, 's, PlanFailed>,
+    pub(crate) plan_completed: EventWriter<'w, 's, PlanCompleted>,
+    pub(crate) action_started: EventWriter<'w, 's, ActionStarted>,
+    pub(crate) action_completed: EventWriter<'w, 's, ActionCompleted>,
+    pub(crate) action_failed: EventWriter<'w, 's, ActionFailed>,
+}
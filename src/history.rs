@@ -0,0 +1,123 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::prelude::Component;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{events::PlanFailureReason, state::GoapState};
+
+/// How a plan recorded in a `PlanHistory` ended.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PlanOutcome {
+    /// The plan is still being executed.
+    InProgress,
+    /// Every `Action` in the plan completed.
+    Completed,
+    /// The plan failed while executing the `Action` at index `step`, e.g. because it returned a
+    /// failure or timed out, or because the plan was invalidated.
+    Failed {
+        step: usize,
+        action: String,
+        reason: PlanFailureReason,
+    },
+    /// The `Actor` requested a new plan before completing the `Action` at index `step` of the plan.
+    Interrupted { step: usize },
+    /// The planner could not find a plan.
+    NoPlan,
+}
+
+/// A planning outcome recorded in a `PlanHistory`.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PlanRecord {
+    /// The time elapsed since startup when the plan was created.
+    pub time: Duration,
+    /// The name and value of each `Condition` in the goal.
+    pub goal: Vec<(String, bool)>,
+    /// The name and value of each `Condition` in the state the plan started from.
+    pub start_state: Vec<(String, bool)>,
+    /// The names of the `Action`s in the plan, in the order they are executed.
+    pub actions: Vec<String>,
+    pub cost: i32,
+    pub outcome: PlanOutcome,
+}
+
+/// Keeps the last planning outcomes of an `Actor`. Insert it on the `Actor`'s entity to record.
+#[derive(Component, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PlanHistory {
+    capacity: usize,
+    records: VecDeque<PlanRecord>,
+}
+
+impl PlanHistory {
+    /// Creates a history keeping the last `capacity` planning outcomes.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// The recorded planning outcomes, from oldest to newest.
+    pub fn records(&self) -> impl DoubleEndedIterator<Item = &PlanRecord> + ExactSizeIterator {
+        self.records.iter()
+    }
+
+    /// The most recent planning outcome.
+    pub fn latest(&self) -> Option<&PlanRecord> {
+        self.records.back()
+    }
+
+    pub(crate) fn push(
+        &mut self,
+        time: Duration,
+        goal: &GoapState,
+        start_state: &GoapState,
+        actions: Vec<String>,
+        cost: i32,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+
+        let outcome = if actions.is_empty() {
+            PlanOutcome::NoPlan
+        } else {
+            PlanOutcome::InProgress
+        };
+
+        self.records.push_back(PlanRecord {
+            time,
+            goal: to_named_values(goal),
+            start_state: to_named_values(start_state),
+            actions,
+            cost,
+            outcome,
+        });
+    }
+
+    /// Sets the outcome of the latest plan, if it is still in progress.
+    pub(crate) fn finish(&mut self, outcome: PlanOutcome) {
+        if let Some(record) = self.records.back_mut() {
+            if record.outcome == PlanOutcome::InProgress {
+                record.outcome = outcome;
+            }
+        }
+    }
+}
+
+fn to_named_values(state: &GoapState) -> Vec<(String, bool)> {
+    let mut values = state
+        .iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect::<Vec<_>>();
+    values.sort();
+    values
+}
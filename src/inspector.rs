@@ -1,7 +1,10 @@
 use bevy::prelude::Plugin;
 use bevy_inspector_egui::{Inspectable, RegisterInspectable, WorldInspectorPlugin};
 
-use crate::{action::Action, actor::ActorState, planning::PlanningState, ActionState, Actor};
+use crate::{
+    action::Action, actor::ActorState, planning::PlanningState, ActionState, Actor, Blackboard,
+    PlanHistory,
+};
 
 pub struct GoapInspectorPlugin;
 
@@ -12,7 +15,9 @@ impl Plugin for GoapInspectorPlugin {
             .register_inspectable::<ActorState>()
             .register_inspectable::<Action>()
             .register_inspectable::<ActionState>()
-            .register_inspectable::<PlanningState>();
+            .register_inspectable::<PlanningState>()
+            .register_inspectable::<PlanHistory>()
+            .register_inspectable::<Blackboard>();
     }
 }
 
@@ -85,3 +90,31 @@ impl Inspectable for PlanningState {
         false
     }
 }
+
+impl Inspectable for PlanHistory {
+    type Attributes = ();
+
+    fn ui(
+        &mut self,
+        ui: &mut bevy_inspector_egui::egui::Ui,
+        _options: Self::Attributes,
+        _context: &mut bevy_inspector_egui::Context,
+    ) -> bool {
+        ui.label(format!("{:#?}", self));
+        false
+    }
+}
+
+impl Inspectable for Blackboard {
+    type Attributes = ();
+
+    fn ui(
+        &mut self,
+        ui: &mut bevy_inspector_egui::egui::Ui,
+        _options: Self::Attributes,
+        _context: &mut bevy_inspector_egui::Context,
+    ) -> bool {
+        ui.label(format!("{:#?}", self));
+        false
+    }
+}
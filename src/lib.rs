@@ -1,89 +1,336 @@
-use action::action_state_system;
-use actor::{actor_state_system, build_new_actor_system};
-use bevy::prelude::{CoreStage, IntoSystemDescriptor, Plugin, StageLabel, SystemSet, SystemStage};
+#[cfg(feature = "bevy")]
+use action::{
+    action_state_system, action_timeout_system, despawn_orphaned_actions_system,
+    validate_action_state_system, LastValidActionState,
+};
+#[cfg(feature = "bevy")]
+use actor::{actor_state_system, build_new_actor_system, goal_changed_system};
+#[cfg(feature = "bevy")]
+use bevy::{
+    ecs::schedule::StageLabelId,
+    prelude::{IntoSystemDescriptor, Plugin, Schedule, StageLabel, SystemSet, SystemStage},
+};
+#[cfg(feature = "bevy")]
+use compound::compound_action_system;
+#[cfg(feature = "bevy")]
+use events::ActionTimedOut;
 
+#[cfg(feature = "bevy")]
 use planning::{
-    create_plan_system, create_planning_state, request_plan_event_handler_system, RequestPlanEvent,
+    create_plan_system, create_planning_state, merge_planning_states_system,
+    replan_on_change_system, request_plan_event_handler_system, PlanningState, RequestPlanEvent,
 };
 
+#[cfg(feature = "bevy")]
 mod action;
+#[cfg(feature = "bevy")]
+mod action_handler;
+#[cfg(feature = "bevy")]
 mod actor;
+#[cfg(feature = "bevy")]
+mod analysis;
+#[cfg(feature = "bevy")]
+mod app;
+#[cfg(feature = "bevy")]
+mod blackboard;
+#[cfg(feature = "bevy")]
 mod common;
+#[cfg(feature = "bevy")]
+mod compound;
 mod condition;
+#[cfg(feature = "domain")]
+mod domain;
+#[cfg(feature = "bevy")]
+mod dot;
+#[cfg(feature = "bevy")]
+mod events;
+#[cfg(feature = "bevy")]
+mod history;
+#[cfg(feature = "bevy")]
+mod metrics;
+pub mod planner;
+#[cfg(feature = "bevy")]
 mod planning;
+#[cfg(feature = "bevy")]
+mod schedule;
+#[cfg(feature = "bevy")]
+mod sensor;
+#[cfg(feature = "bevy")]
+mod settings;
+#[cfg(feature = "bevy")]
+mod snapshot;
+#[cfg(feature = "bevy")]
 mod state;
 
 #[cfg(feature = "inspector")]
 pub mod inspector;
 
-pub use action::{Action, ActionState, EvaluationResult};
+/// Items used by the code generated by `goap_action`, so that it does not depend on how users
+/// depend on Bevy. Not part of the public API.
+#[cfg(feature = "bevy")]
+#[doc(hidden)]
+pub mod __private {
+    pub use bevy::prelude::Query;
+}
+
+#[cfg(feature = "bevy")]
+pub use action::{Action, ActionState, EvaluationResult, InvalidActionTransition};
+#[cfg(feature = "bevy")]
+pub use action_handler::{ActionProgress, GoapAction};
+#[cfg(feature = "bevy")]
 pub use actor::{Actor, ActorState};
+#[cfg(feature = "bevy")]
+pub use analysis::{
+    DomainIssue, DomainReport, ExcludedAction, NoPlanExplanation, UnreachablePreconditions,
+};
+#[cfg(feature = "bevy")]
+pub use app::{GoapAppExt, GoapScheduleExt};
+pub use bevy_goap_derive::{goap_action, Condition};
+#[cfg(feature = "bevy")]
+pub use blackboard::{Blackboard, BlackboardKey};
+#[cfg(feature = "bevy")]
+pub use compound::{
+    CompoundAction, CompoundActionBuilder, SequenceBuilder, SequenceDecomposition, SubGoalBuilder,
+    SubGoalDecomposition, SubPlan,
+};
 pub use condition::Condition;
+#[cfg(feature = "domain")]
+pub use domain::{ActionDefinition, ActorTemplate, DomainError, DomainProblem, GoapDomain};
+#[cfg(feature = "assets")]
+pub use domain::{
+    DomainAction, DomainActor, GoapAssetPlugin, GoapDomainLoader, GoapMarkerRegistry,
+};
+#[cfg(feature = "bevy")]
+pub use dot::domain_to_dot;
+#[cfg(feature = "bevy")]
+pub use events::{
+    ActionCompleted, ActionFailed, ActionStarted, GoalChanged, PlanCompleted, PlanCreated,
+    PlanFailed, PlanFailureReason,
+};
+#[cfg(feature = "bevy")]
+pub use history::{PlanHistory, PlanOutcome, PlanRecord};
+#[cfg(feature = "bevy")]
+pub use metrics::{GoapDiagnosticsPlugin, GoapMetrics};
+#[cfg(feature = "bevy")]
+pub use planning::{SearchTree, SearchTreeNode};
+#[cfg(feature = "bevy")]
+pub use schedule::{run_in_state, GoapSchedule, GoapScheduleLabel};
+#[cfg(feature = "bevy")]
+pub use sensor::{Sensor, SensorUpdate};
+#[cfg(feature = "bevy")]
+pub use settings::{
+    GoapLogLevel, GoapLogSettings, PlannerSettings, PlannerStrategy, TimeoutSettings,
+};
+#[cfg(feature = "bevy")]
+pub use snapshot::{ActionSnapshot, ActorSnapshot, SnapshotError};
+#[cfg(all(feature = "bevy", feature = "serde"))]
+pub use state::ConditionNameConflict;
+
+#[cfg(feature = "bevy")]
+#[derive(Clone, Debug)]
+pub struct GoapPlugin {
+    /// Where the crate's systems run. Defaults to `GoapSchedule::Main` with the core stages.
+    pub schedule: GoapSchedule,
+    pub planner: PlannerSettings,
+    /// The verbosity of the crate's logging. Defaults to `GoapLogLevel::Off` for every subsystem.
+    pub log: GoapLogSettings,
+    pub timeouts: TimeoutSettings,
+    /// Whether to despawn the `Action`s of an `Actor` along with it. Defaults to `true`.
+    pub despawn_actions_with_actor: bool,
+}
 
-pub struct GoapPlugin;
+#[cfg(feature = "bevy")]
+impl Default for GoapPlugin {
+    fn default() -> Self {
+        Self {
+            schedule: GoapSchedule::default(),
+            planner: PlannerSettings::default(),
+            log: GoapLogSettings::default(),
+            timeouts: TimeoutSettings::default(),
+            despawn_actions_with_actor: true,
+        }
+    }
+}
 
-impl Plugin for GoapPlugin {
-    fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_event::<RequestPlanEvent>();
+#[cfg(feature = "bevy")]
+impl GoapPlugin {
+    /// Creates a `Schedule` that runs all of the crate's systems in order: building `Actor`s,
+    /// `GoapStage::Actions`, `Action` transitions, `GoapStage::Actors`, `GoapStage::Sensors`,
+    /// `Actor` transitions, and planning.
+    ///
+    /// Use this with `GoapSchedule::Manual` to add the schedule to a stage of your own schedule,
+    /// e.g. one with a fixed timestep. User systems should be added to the `GoapStage`s of the
+    /// returned schedule, see `GoapScheduleExt`.
+    pub fn create_schedule(&self) -> Schedule {
+        let mut schedule = Schedule::default();
+        schedule.add_stage(InternalGoapStage::Build, SystemStage::parallel());
+        schedule.add_stage_after(
+            InternalGoapStage::Build,
+            InternalGoapStage::Planning,
+            SystemStage::parallel(),
+        );
 
-        app.add_startup_system(create_planning_state);
+        self.add_systems(
+            &mut schedule,
+            InternalGoapStage::Build.as_label(),
+            InternalGoapStage::Build.as_label(),
+            InternalGoapStage::Planning.as_label(),
+        );
 
-        app.add_system_to_stage(CoreStage::First, build_new_actor_system);
+        schedule
+    }
+
+    fn add_systems(
+        &self,
+        schedule: &mut Schedule,
+        build_stage: StageLabelId,
+        goap_stages_after: StageLabelId,
+        planning_stage: StageLabelId,
+    ) {
+        schedule.add_system_to_stage(build_stage, build_new_actor_system);
+        schedule.add_system_to_stage(build_stage, merge_planning_states_system);
+
+        if self.despawn_actions_with_actor {
+            schedule.add_system_to_stage(build_stage, despawn_orphaned_actions_system);
+        }
 
         // User Action systems should be added to this stage, which can check for progress of Actions after typical user systems (e.g. for movement) complete during Update.
-        app.add_stage_after(
-            CoreStage::Update,
+        schedule.add_stage_after(
+            goap_stages_after,
             GoapStage::Actions,
             SystemStage::parallel(),
         );
 
         // We add another stage for change detection of completed or failed actions, which may update the ActorState to reflect a completed or failed plan.
-        app.add_stage_after(
+        schedule.add_stage_after(
             GoapStage::Actions,
             InternalGoapStage::ActionStateTransition,
             SystemStage::parallel(),
         );
-        app.add_system_to_stage(
+        schedule.add_system_set_to_stage(
             InternalGoapStage::ActionStateTransition,
-            action_state_system,
+            SystemSet::new()
+                .with_system(compound_action_system)
+                .with_system(validate_action_state_system.after(compound_action_system))
+                .with_system(action_timeout_system.after(validate_action_state_system))
+                .with_system(action_state_system.after(action_timeout_system)),
         );
 
         // User Actor systems should be added to this stage, which can react to an Actor's completed or failed plan.
-        app.add_stage_after(
+        schedule.add_stage_after(
             InternalGoapStage::ActionStateTransition,
             GoapStage::Actors,
             SystemStage::parallel(),
         );
 
-        // We add another stage for change detection of ActorStates for Actors that may require a new plan.
-        app.add_stage_after(
+        // Sensors run after user Actor systems, so that they see Actors that were transitioned to
+        // ActorState::RequiresPlan.
+        schedule.add_stage_after(
             GoapStage::Actors,
+            GoapStage::Sensors,
+            SystemStage::parallel(),
+        );
+
+        // Sensors that only run before planning come last, so that they also see Actors that
+        // another Sensor, or a changed Condition, transitioned to ActorState::RequiresPlan.
+        schedule.add_stage_after(
+            GoapStage::Sensors,
+            InternalGoapStage::PlanningSensors,
+            SystemStage::parallel(),
+        );
+        schedule.add_system_to_stage(InternalGoapStage::PlanningSensors, replan_on_change_system);
+
+        // We add another stage for change detection of ActorStates for Actors that may require a
+        // new plan.
+        schedule.add_stage_after(
+            InternalGoapStage::PlanningSensors,
             InternalGoapStage::ActorStateTransition,
             SystemStage::parallel(),
         );
-        app.add_system_set_to_stage(
+        schedule.add_system_set_to_stage(
             InternalGoapStage::ActorStateTransition,
             SystemSet::new()
+                .with_system(goal_changed_system)
                 .with_system(actor_state_system)
                 .with_system(request_plan_event_handler_system.after(actor_state_system)),
         );
 
-        app.add_system_to_stage(CoreStage::Last, create_plan_system);
+        schedule.add_system_to_stage(planning_stage, create_plan_system);
+    }
+}
+
+#[cfg(feature = "bevy")]
+impl Plugin for GoapPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.add_event::<RequestPlanEvent>()
+            .add_event::<InvalidActionTransition>()
+            .add_event::<PlanCreated>()
+            .add_event::<PlanFailed>()
+            .add_event::<PlanCompleted>()
+            .add_event::<ActionStarted>()
+            .add_event::<ActionCompleted>()
+            .add_event::<ActionFailed>()
+            .add_event::<GoalChanged>()
+            .add_event::<ActionTimedOut>();
+
+        app.insert_resource(self.planner.clone())
+            .insert_resource(self.log.clone())
+            .insert_resource(self.timeouts.clone())
+            .init_resource::<GoapMetrics>();
+
+        app.register_type::<Actor>()
+            .register_type::<ActorState>()
+            .register_type::<Action>()
+            .register_type::<ActionState>()
+            .register_type::<EvaluationResult>()
+            .register_type::<LastValidActionState>()
+            .register_type::<PlanningState>();
+
+        app.add_startup_system(create_planning_state);
+
+        match self.schedule {
+            GoapSchedule::Main {
+                build_stage,
+                goap_stages_after,
+                planning_stage,
+            } => {
+                self.add_systems(
+                    &mut app.schedule,
+                    build_stage,
+                    goap_stages_after,
+                    planning_stage,
+                );
+            }
+            GoapSchedule::Nested { after } => {
+                app.add_stage_after(after, GoapScheduleLabel, self.create_schedule());
+            }
+            GoapSchedule::Manual => {}
+        }
     }
 }
 
+#[cfg(feature = "bevy")]
 #[derive(StageLabel)]
 pub enum GoapStage {
     /// User `Action` systems should be added to this stage.
     Actions,
     /// User `Actor` systems should be added to this stage.
     Actors,
+    /// `SensorUpdate::EveryUpdate` `Sensor`s run in this stage, see `GoapAppExt::add_goap_sensor`.
+    Sensors,
 }
 
+#[cfg(feature = "bevy")]
 #[derive(StageLabel)]
 enum InternalGoapStage {
+    /// Internal stage to build new `Actor`s, when the crate's systems run in their own `Schedule`.
+    Build,
     /// Internal stage to react to changed `ActionState`s from user `Action` systems.
     ActionStateTransition,
+    /// Internal stage for `SensorUpdate::BeforePlanning` `Sensor`s, after `GoapStage::Sensors`.
+    PlanningSensors,
     /// Internal stage to react to changed `ActorState`s from user `Actor` systems.
     ActorStateTransition,
+    /// Internal stage to create plans, when the crate's systems run in their own `Schedule`.
+    Planning,
 }
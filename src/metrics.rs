@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticId, Diagnostics},
+    prelude::{App, CoreStage, Plugin, Res, ResMut, Resource},
+};
+
+/// Statistics of the planner, updated by the crate's planning system.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct GoapMetrics {
+    /// The number of plan requests handled during the last frame, including cache hits, requests
+    /// without a plan and checks for a cheaper plan.
+    pub plans_last_frame: usize,
+    /// The number of searches performed by the planner, i.e. plan requests that missed the cache.
+    pub searches: u64,
+    /// The total number of nodes expanded over all searches.
+    pub total_nodes_expanded: u64,
+    /// The most nodes expanded by a single search.
+    pub max_nodes_expanded: usize,
+    /// The total duration of all searches.
+    pub total_search_time: Duration,
+    /// The longest duration of a single search.
+    pub max_search_time: Duration,
+    /// The number of plan requests remaining in the queue after the last frame.
+    pub queue_length: usize,
+    /// The number of times a plan request was re-queued because the `Action`s of its `Actor` had
+    /// not finished evaluating.
+    pub requeues_pending_evaluation: u64,
+    /// The number of times a plan request was re-queued because
+    /// `PlannerSettings::max_plans_per_frame` was reached.
+    pub requeues_plan_budget: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    /// The number of plans repaired rather than searched for from scratch, see
+    /// `PlannerSettings::repair_plans`.
+    pub plans_repaired: u64,
+    /// The number of failed plan repairs, each followed by a full search.
+    pub failed_repairs: u64,
+}
+
+impl GoapMetrics {
+    /// The average number of nodes expanded per search.
+    pub fn avg_nodes_expanded(&self) -> f64 {
+        if self.searches == 0 {
+            0.0
+        } else {
+            self.total_nodes_expanded as f64 / self.searches as f64
+        }
+    }
+
+    /// The average duration of a search.
+    pub fn avg_search_time(&self) -> Duration {
+        if self.searches == 0 {
+            Duration::ZERO
+        } else {
+            self.total_search_time.div_f64(self.searches as f64)
+        }
+    }
+
+    /// The fraction of plan requests served from the plan cache, or `None` if the cache has not
+    /// been used.
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        let lookups = self.cache_hits + self.cache_misses;
+
+        (lookups > 0).then(|| self.cache_hits as f64 / lookups as f64)
+    }
+
+    pub(crate) fn record_search(&mut self, nodes_expanded: usize, search_time: Duration) {
+        self.searches += 1;
+        self.total_nodes_expanded += nodes_expanded as u64;
+        self.max_nodes_expanded = self.max_nodes_expanded.max(nodes_expanded);
+        self.total_search_time += search_time;
+        self.max_search_time = self.max_search_time.max(search_time);
+    }
+}
+
+/// Adds the `GoapMetrics` to Bevy's `Diagnostics`, e.g. to be output by the `LogDiagnosticsPlugin`.
+pub struct GoapDiagnosticsPlugin;
+
+impl GoapDiagnosticsPlugin {
+    pub const PLANS_PER_FRAME: DiagnosticId =
+        DiagnosticId::from_u128(0x8d6e_39c2_41a4_4f4b_9c1e_5a0f_3b7d_1e01);
+    pub const AVG_NODES_EXPANDED: DiagnosticId =
+        DiagnosticId::from_u128(0x8d6e_39c2_41a4_4f4b_9c1e_5a0f_3b7d_1e02);
+    pub const MAX_NODES_EXPANDED: DiagnosticId =
+        DiagnosticId::from_u128(0x8d6e_39c2_41a4_4f4b_9c1e_5a0f_3b7d_1e03);
+    pub const AVG_SEARCH_TIME: DiagnosticId =
+        DiagnosticId::from_u128(0x8d6e_39c2_41a4_4f4b_9c1e_5a0f_3b7d_1e04);
+    pub const MAX_SEARCH_TIME: DiagnosticId =
+        DiagnosticId::from_u128(0x8d6e_39c2_41a4_4f4b_9c1e_5a0f_3b7d_1e05);
+    pub const QUEUE_LENGTH: DiagnosticId =
+        DiagnosticId::from_u128(0x8d6e_39c2_41a4_4f4b_9c1e_5a0f_3b7d_1e06);
+    pub const REQUEUES_PENDING_EVALUATION: DiagnosticId =
+        DiagnosticId::from_u128(0x8d6e_39c2_41a4_4f4b_9c1e_5a0f_3b7d_1e07);
+    pub const CACHE_HIT_RATE: DiagnosticId =
+        DiagnosticId::from_u128(0x8d6e_39c2_41a4_4f4b_9c1e_5a0f_3b7d_1e08);
+}
+
+impl Plugin for GoapDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GoapMetrics>()
+            .add_startup_system(setup_diagnostics_system)
+            .add_system_to_stage(CoreStage::PostUpdate, diagnostics_system);
+    }
+}
+
+fn setup_diagnostics_system(mut diagnostics: ResMut<Diagnostics>) {
+    for (id, name, suffix) in [
+        (
+            GoapDiagnosticsPlugin::PLANS_PER_FRAME,
+            "goap_plans_per_frame",
+            "",
+        ),
+        (
+            GoapDiagnosticsPlugin::AVG_NODES_EXPANDED,
+            "goap_avg_nodes_expanded",
+            "",
+        ),
+        (
+            GoapDiagnosticsPlugin::MAX_NODES_EXPANDED,
+            "goap_max_nodes_expanded",
+            "",
+        ),
+        (
+            GoapDiagnosticsPlugin::AVG_SEARCH_TIME,
+            "goap_avg_search_time",
+            "ms",
+        ),
+        (
+            GoapDiagnosticsPlugin::MAX_SEARCH_TIME,
+            "goap_max_search_time",
+            "ms",
+        ),
+        (GoapDiagnosticsPlugin::QUEUE_LENGTH, "goap_queue_length", ""),
+        (
+            GoapDiagnosticsPlugin::REQUEUES_PENDING_EVALUATION,
+            "goap_requeues_pending_evaluation",
+            "",
+        ),
+        (
+            GoapDiagnosticsPlugin::CACHE_HIT_RATE,
+            "goap_cache_hit_rate",
+            "%",
+        ),
+    ] {
+        diagnostics.add(Diagnostic::new(id, name, 20).with_suffix(suffix));
+    }
+}
+
+fn diagnostics_system(metrics: Res<GoapMetrics>, mut diagnostics: ResMut<Diagnostics>) {
+    diagnostics.add_measurement(GoapDiagnosticsPlugin::PLANS_PER_FRAME, || {
+        metrics.plans_last_frame as f64
+    });
+    diagnostics.add_measurement(GoapDiagnosticsPlugin::AVG_NODES_EXPANDED, || {
+        metrics.avg_nodes_expanded()
+    });
+    diagnostics.add_measurement(GoapDiagnosticsPlugin::MAX_NODES_EXPANDED, || {
+        metrics.max_nodes_expanded as f64
+    });
+    diagnostics.add_measurement(GoapDiagnosticsPlugin::AVG_SEARCH_TIME, || {
+        metrics.avg_search_time().as_secs_f64() * 1000.0
+    });
+    diagnostics.add_measurement(GoapDiagnosticsPlugin::MAX_SEARCH_TIME, || {
+        metrics.max_search_time.as_secs_f64() * 1000.0
+    });
+    diagnostics.add_measurement(GoapDiagnosticsPlugin::QUEUE_LENGTH, || {
+        metrics.queue_length as f64
+    });
+    diagnostics.add_measurement(GoapDiagnosticsPlugin::REQUEUES_PENDING_EVALUATION, || {
+        metrics.requeues_pending_evaluation as f64
+    });
+    diagnostics.add_measurement(GoapDiagnosticsPlugin::CACHE_HIT_RATE, || {
+        metrics.cache_hit_rate().unwrap_or(0.0) * 100.0
+    });
+}
@@ -0,0 +1,657 @@
+//! The planner, independent of Bevy, so that it can be used by tools, tests and other consumers.
+//! The `GoapPlugin` plans with this module, using `Entity`s as `Action` ids.
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+};
+
+use pathfinding::prelude::astar;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The value of each condition, identified by `C`, e.g. the name of the condition.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(
+        transparent,
+        bound(
+            serialize = "C: Serialize + Eq + Hash",
+            deserialize = "C: Deserialize<'de> + Eq + Hash"
+        )
+    )
+)]
+pub struct State<C> {
+    values: HashMap<C, bool>,
+}
+
+impl<C> Default for State<C> {
+    fn default() -> Self {
+        Self {
+            values: HashMap::new(),
+        }
+    }
+}
+
+impl<C: Eq + Hash> PartialEq for State<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+
+impl<C: Eq + Hash> Eq for State<C> {}
+
+impl<C: Hash> Hash for State<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Combine the hashes of the entries independently of the iteration order of the map, so
+        // that equal states hash equally.
+        let combined = self.values.iter().fold(0u64, |combined, entry| {
+            let mut hasher = DefaultHasher::new();
+            entry.hash(&mut hasher);
+            combined.wrapping_add(hasher.finish())
+        });
+
+        combined.hash(state);
+    }
+}
+
+impl<C: Eq + Hash> FromIterator<(C, bool)> for State<C> {
+    fn from_iter<I: IntoIterator<Item = (C, bool)>>(iter: I) -> Self {
+        Self {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<C: Eq + Hash> State<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value of `condition`, returning this state.
+    pub fn with(mut self, condition: C, value: bool) -> Self {
+        self.insert(condition, value);
+        self
+    }
+
+    pub fn insert(&mut self, condition: C, value: bool) {
+        self.values.insert(condition, value);
+    }
+
+    pub fn remove(&mut self, condition: &C) {
+        self.values.remove(condition);
+    }
+
+    /// The value of `condition`, or `None` if this state does not contain it.
+    pub fn get(&self, condition: &C) -> Option<bool> {
+        self.values.get(condition).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&C, bool)> + '_ {
+        self.values
+            .iter()
+            .map(|(condition, value)| (condition, *value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Sets the value of every condition in `other`.
+    pub fn extend(&mut self, other: State<C>) {
+        self.values.extend(other.values);
+    }
+
+    /// The number of conditions in `target` that differ from, or are missing in, this state.
+    pub fn mismatch_count(&self, target: &State<C>) -> i32 {
+        target
+            .iter()
+            .filter(|(condition, value)| self.get(condition) != Some(*value))
+            .count() as i32
+    }
+
+    /// Whether every condition in `target` has the same value in this state.
+    pub fn satisfies(&self, target: &State<C>) -> bool {
+        self.mismatch_count(target) == 0
+    }
+}
+
+/// An action the planner can choose, identified by `A`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Action<C: Eq + Hash, A> {
+    pub id: A,
+    /// The conditions that must be met for the action to be chosen.
+    pub preconditions: State<C>,
+    /// The conditions that are set once the action completes.
+    pub effects: State<C>,
+    pub cost: i32,
+}
+
+impl<C: Eq + Hash, A> Action<C, A> {
+    /// Creates an action without preconditions or effects, with a cost of 1.
+    pub fn new(id: A) -> Self {
+        Self {
+            id,
+            preconditions: State::new(),
+            effects: State::new(),
+            cost: 1,
+        }
+    }
+
+    pub fn with_precondition(mut self, condition: C, value: bool) -> Self {
+        self.preconditions.insert(condition, value);
+        self
+    }
+
+    pub fn with_effect(mut self, condition: C, value: bool) -> Self {
+        self.effects.insert(condition, value);
+        self
+    }
+
+    pub fn with_cost(mut self, cost: i32) -> Self {
+        self.cost = cost;
+        self
+    }
+}
+
+/// A set of actions to plan with.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Domain<C: Eq + Hash, A> {
+    pub actions: Vec<Action<C, A>>,
+}
+
+impl<C: Eq + Hash, A> Default for Domain<C, A> {
+    fn default() -> Self {
+        Self { actions: vec![] }
+    }
+}
+
+impl<C: Clone + Eq + Hash, A: Clone + Eq + Hash> Domain<C, A> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_action(mut self, action: Action<C, A>) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    /// Finds the cheapest plan from `start` to `goal`, see `plan`.
+    pub fn plan(&self, start: &State<C>, goal: &State<C>) -> Option<Plan<C, A>> {
+        plan(start, goal, &self.actions)
+    }
+}
+
+/// A sequence of actions reaching a goal.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Plan<C: Eq + Hash, A> {
+    /// The ids of the actions, in the order they are executed.
+    pub actions: Vec<A>,
+    pub cost: i32,
+    /// The state once every action has completed.
+    pub end_state: State<C>,
+}
+
+/// The search strategy used by the planner.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PlannerStrategy {
+    /// A* search, using the number of unsatisfied goal conditions as the heuristic.
+    #[default]
+    AStar,
+    /// Uniform cost search, which ignores the heuristic. This always finds the cheapest plan, even
+    /// if `Action` costs are lower than the number of conditions they satisfy, at the expense of
+    /// expanding more nodes.
+    Dijkstra,
+}
+
+/// Options of a `search`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct SearchOptions {
+    pub strategy: PlannerStrategy,
+    /// The maximum number of nodes to expand. Once exceeded, only nodes that were already generated
+    /// can reach the goal, so a more expensive plan, or no plan, may be found.
+    pub max_expanded_nodes: Option<usize>,
+}
+
+/// A node of the search, i.e. the state reached by an action.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SearchNode<C: Eq + Hash, A> {
+    /// The id of the action leading to this node, or `None` for the start node.
+    pub action: Option<A>,
+    pub state: State<C>,
+}
+
+/// The result of a `search`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SearchResult<C: Eq + Hash, A> {
+    pub plan: Option<Plan<C, A>>,
+    /// The nodes from the start node to the goal, or empty if the goal cannot be reached.
+    pub path: Vec<SearchNode<C, A>>,
+    pub expanded_nodes: usize,
+}
+
+/// Finds the cheapest plan from `start` to `goal` with the default `SearchOptions`. Returns `None`
+/// if the goal cannot be reached, or is already met by `start`.
+pub fn plan<C: Clone + Eq + Hash, A: Clone + Eq + Hash>(
+    start: &State<C>,
+    goal: &State<C>,
+    actions: &[Action<C, A>],
+) -> Option<Plan<C, A>> {
+    search(start, goal, actions, &SearchOptions::default(), |_, _| {}).plan
+}
+
+/// The heuristic estimate of the cost from `state` to `goal`.
+pub fn heuristic<C: Eq + Hash>(
+    state: &State<C>,
+    goal: &State<C>,
+    strategy: PlannerStrategy,
+) -> i32 {
+    match strategy {
+        PlannerStrategy::AStar => state.mismatch_count(goal),
+        PlannerStrategy::Dijkstra => 0,
+    }
+}
+
+/// Searches for the cheapest plan from `start` to `goal`, calling `on_expand` with each expanded
+/// node and its successors, with the cost of the action leading to each successor.
+pub fn search<C: Clone + Eq + Hash, A: Clone + Eq + Hash>(
+    start: &State<C>,
+    goal: &State<C>,
+    actions: &[Action<C, A>],
+    options: &SearchOptions,
+    mut on_expand: impl FnMut(&SearchNode<C, A>, &[(SearchNode<C, A>, i32)]),
+) -> SearchResult<C, A> {
+    let start_node = SearchNode {
+        action: None,
+        state: start.clone(),
+    };
+    let mut expanded_nodes = 0;
+
+    let (path, cost) = astar(
+        &start_node,
+        |node| {
+            expanded_nodes += 1;
+
+            if options
+                .max_expanded_nodes
+                .is_some_and(|max_nodes| expanded_nodes > max_nodes)
+            {
+                // Returning no successors once the budget is exceeded exhausts the search.
+                return vec![];
+            }
+
+            let successors = successors(node, actions);
+            on_expand(node, &successors);
+            successors
+        },
+        |node| heuristic(&node.state, goal, options.strategy),
+        |node| node.state.satisfies(goal),
+    )
+    .unwrap_or((vec![], 0));
+
+    let plan = path.last().filter(|_| path.len() > 1).map(|end_node| Plan {
+        actions: path.iter().filter_map(|node| node.action.clone()).collect(),
+        cost,
+        end_state: end_node.state.clone(),
+    });
+
+    SearchResult {
+        plan,
+        path,
+        expanded_nodes,
+    }
+}
+
+/// Repairs a plan whose remaining actions are `path`, rather than searching for a new plan from
+/// scratch, keeping a suffix of `path` that can still reach `goal`.
+///
+/// If any suffix can be executed from `start`, the shortest one is kept. Otherwise, a single search
+/// finds a bridge from `start` to the state required by the first action of any suffix, minimizing
+/// the cost of the bridge and the suffix together, and the suffix is kept, preceded by the bridge.
+/// The repaired plan may be more expensive than the cheapest plan. Its `plan` is `None` if no
+/// suffix can be bridged, in which case a full `search` is required.
+pub fn repair<C: Clone + Eq + Hash, A: Clone + Eq + Hash>(
+    start: &State<C>,
+    goal: &State<C>,
+    path: &[A],
+    actions: &[Action<C, A>],
+    options: &SearchOptions,
+) -> SearchResult<C, A> {
+    let suffixes = (0..path.len())
+        .filter_map(|index| {
+            let suffix = path[index..]
+                .iter()
+                .map(|id| actions.iter().find(|action| action.id == *id))
+                .collect::<Option<Vec<_>>>()?;
+            let required = regress(goal, &suffix)?;
+            let cost = suffix.iter().map(|action| action.cost).sum::<i32>();
+
+            Some((suffix, required, cost))
+        })
+        .collect::<Vec<_>>();
+
+    let start_node = SearchNode {
+        action: None,
+        state: start.clone(),
+    };
+
+    if let Some((suffix, _, _)) = suffixes
+        .iter()
+        .rev()
+        .find(|(_, required, _)| start.satisfies(required))
+    {
+        return repaired(vec![start_node], 0, suffix, 0);
+    }
+
+    if suffixes.is_empty() {
+        return SearchResult {
+            plan: None,
+            path: vec![],
+            expanded_nodes: 0,
+        };
+    }
+
+    let mut expanded_nodes = 0;
+
+    // Reaching the state required by a suffix finishes the plan at the cost of the suffix.
+    let result = astar(
+        &RepairNode::Bridge(start_node),
+        |node| {
+            let RepairNode::Bridge(node) = node else {
+                return vec![];
+            };
+
+            expanded_nodes += 1;
+
+            if options
+                .max_expanded_nodes
+                .is_some_and(|max_nodes| expanded_nodes > max_nodes)
+            {
+                return vec![];
+            }
+
+            successors(node, actions)
+                .into_iter()
+                .map(|(successor, cost)| (RepairNode::Bridge(successor), cost))
+                .chain(
+                    suffixes
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, (_, required, _))| node.state.satisfies(required))
+                        .map(|(index, (_, _, cost))| (RepairNode::Suffix(index), *cost)),
+                )
+                .collect()
+        },
+        |node| match node {
+            RepairNode::Bridge(node) => suffixes
+                .iter()
+                .map(|(_, required, cost)| {
+                    heuristic(&node.state, required, options.strategy) + cost
+                })
+                .min()
+                .unwrap_or_default(),
+            RepairNode::Suffix(_) => 0,
+        },
+        |node| matches!(node, RepairNode::Suffix(_)),
+    );
+
+    let Some((nodes, cost)) = result else {
+        return SearchResult {
+            plan: None,
+            path: vec![],
+            expanded_nodes,
+        };
+    };
+
+    // The search ends with the node of a suffix, after the nodes of its bridge.
+    let mut bridge = vec![];
+    let mut suffix_index = 0;
+
+    for node in nodes {
+        match node {
+            RepairNode::Bridge(node) => bridge.push(node),
+            RepairNode::Suffix(index) => suffix_index = index,
+        }
+    }
+
+    let (suffix, _, suffix_cost) = &suffixes[suffix_index];
+
+    repaired(bridge, cost - suffix_cost, suffix, expanded_nodes)
+}
+
+/// A node of the search of a `repair`: either a node of the bridge, or the end of the plan after
+/// the suffix at the given index.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum RepairNode<C: Eq + Hash, A> {
+    Bridge(SearchNode<C, A>),
+    Suffix(usize),
+}
+
+/// The result of a `repair`, executing `suffix` after the nodes of `bridge`.
+fn repaired<C: Clone + Eq + Hash, A: Clone + Eq + Hash>(
+    bridge: Vec<SearchNode<C, A>>,
+    bridge_cost: i32,
+    suffix: &[&Action<C, A>],
+    expanded_nodes: usize,
+) -> SearchResult<C, A> {
+    let mut path = bridge;
+
+    for action in suffix {
+        let mut state = path.last().unwrap().state.clone();
+        state.extend(action.effects.clone());
+
+        path.push(SearchNode {
+            action: Some(action.id.clone()),
+            state,
+        });
+    }
+
+    let cost = bridge_cost + suffix.iter().map(|action| action.cost).sum::<i32>();
+
+    SearchResult {
+        plan: Some(Plan {
+            actions: path.iter().filter_map(|node| node.action.clone()).collect(),
+            cost,
+            end_state: path.last().unwrap().state.clone(),
+        }),
+        path,
+        expanded_nodes,
+    }
+}
+
+/// The conditions that must be met before executing `actions` in order for them to reach `goal`, or
+/// `None` if they cannot reach `goal` from any state.
+pub fn regress<C: Clone + Eq + Hash, A>(
+    goal: &State<C>,
+    actions: &[&Action<C, A>],
+) -> Option<State<C>> {
+    let mut required = goal.clone();
+
+    for action in actions.iter().rev() {
+        for (condition, value) in action.effects.iter() {
+            match required.get(condition) {
+                Some(required_value) if required_value != value => return None,
+                Some(_) => required.remove(condition),
+                None => {}
+            }
+        }
+
+        for (condition, value) in action.preconditions.iter() {
+            match required.get(condition) {
+                Some(required_value) if required_value != value => return None,
+                _ => required.insert(condition.clone(), value),
+            }
+        }
+    }
+
+    Some(required)
+}
+
+fn successors<C: Clone + Eq + Hash, A: Clone>(
+    node: &SearchNode<C, A>,
+    actions: &[Action<C, A>],
+) -> Vec<(SearchNode<C, A>, i32)> {
+    actions
+        .iter()
+        .filter(|action| node.state.satisfies(&action.preconditions))
+        .map(|action| {
+            let mut state = node.state.clone();
+            state.extend(action.effects.clone());
+
+            (
+                SearchNode {
+                    action: Some(action.id.clone()),
+                    state,
+                },
+                action.cost,
+            )
+        })
+        .collect()
+}
+
+/// The condition values that can be reached from a state, when ignoring that actions may undo each
+/// other's effects.
+///
+/// If a condition value is not reachable in this relaxed problem, no plan can reach it either.
+pub struct Reachability<C> {
+    reachable: HashSet<(C, bool)>,
+}
+
+impl<C: Clone + Eq + Hash> Reachability<C> {
+    /// Analyses the reachability from `start`, given the preconditions and effects of each action.
+    pub fn analyse<'a>(
+        start: &State<C>,
+        actions: impl IntoIterator<Item = (&'a State<C>, &'a State<C>)> + Clone,
+    ) -> Self
+    where
+        C: 'a,
+    {
+        let mut reachable = start
+            .iter()
+            .map(|(condition, value)| (condition.clone(), value))
+            .collect::<HashSet<_>>();
+
+        loop {
+            let reachable_count = reachable.len();
+
+            for (preconditions, effects) in actions.clone() {
+                if preconditions
+                    .iter()
+                    .all(|(condition, value)| reachable.contains(&(condition.clone(), value)))
+                {
+                    reachable.extend(
+                        effects
+                            .iter()
+                            .map(|(condition, value)| (condition.clone(), value)),
+                    );
+                }
+            }
+
+            if reachable.len() == reachable_count {
+                return Self { reachable };
+            }
+        }
+    }
+
+    /// Whether `condition` can reach `value`.
+    pub fn is_reachable(&self, condition: &C, value: bool) -> bool {
+        self.reachable.contains(&(condition.clone(), value))
+    }
+
+    /// Each condition and value in `state` that is not reachable.
+    pub fn unreachable(&self, state: &State<C>) -> Vec<(C, bool)> {
+        state
+            .iter()
+            .filter(|(condition, value)| !self.is_reachable(condition, *value))
+            .map(|(condition, value)| (condition.clone(), value))
+            .collect()
+    }
+}
+
+/// Each condition and value in `target` that is neither met by `start` nor an effect of any action.
+pub fn unproducible<'a, C: Clone + Eq + Hash + 'a>(
+    target: &State<C>,
+    start: &State<C>,
+    effects: impl IntoIterator<Item = &'a State<C>> + Clone,
+) -> Vec<(C, bool)> {
+    target
+        .iter()
+        .filter(|(condition, value)| {
+            start.get(condition) != Some(*value)
+                && !effects
+                    .clone()
+                    .into_iter()
+                    .any(|effects| effects.get(condition) == Some(*value))
+        })
+        .map(|(condition, value)| (condition.clone(), value))
+        .collect()
+}
+
+/// Explains why no plan reaches a goal, see `explain`.
+///
+/// The analysis ignores that actions may undo each other's effects. If every list is empty, the
+/// goal is reachable in that relaxed sense, so the plan was prevented by conflicting effects, or by
+/// `SearchOptions::max_expanded_nodes`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Explanation<C, A> {
+    /// Each goal condition and value that is not met by the start state, and is not an effect of
+    /// any action.
+    pub unproducible_goals: Vec<(C, bool)>,
+    /// Each goal condition and value that cannot be reached from the start state.
+    pub unreachable_goals: Vec<(C, bool)>,
+    /// The id of each action that could never be chosen, with its unreachable preconditions.
+    pub unreachable_preconditions: Vec<(A, Vec<(C, bool)>)>,
+}
+
+impl<C, A> Explanation<C, A> {
+    /// Whether the goal is reachable when ignoring that actions may undo each other's effects.
+    pub fn is_empty(&self) -> bool {
+        self.unproducible_goals.is_empty()
+            && self.unreachable_goals.is_empty()
+            && self.unreachable_preconditions.is_empty()
+    }
+}
+
+/// Explains why no plan reaches `goal` from `start`. Conditions are sorted, so that the explanation
+/// is stable.
+pub fn explain<C: Clone + Ord + Hash, A: Clone>(
+    start: &State<C>,
+    goal: &State<C>,
+    actions: &[Action<C, A>],
+) -> Explanation<C, A> {
+    let reachability = Reachability::analyse(
+        start,
+        actions
+            .iter()
+            .map(|action| (&action.preconditions, &action.effects)),
+    );
+
+    let sorted = |mut values: Vec<(C, bool)>| {
+        values.sort();
+        values
+    };
+
+    Explanation {
+        unproducible_goals: sorted(unproducible(
+            goal,
+            start,
+            actions.iter().map(|action| &action.effects),
+        )),
+        unreachable_goals: sorted(reachability.unreachable(goal)),
+        unreachable_preconditions: actions
+            .iter()
+            .filter_map(|action| {
+                let preconditions = sorted(reachability.unreachable(&action.preconditions));
+
+                (!preconditions.is_empty()).then(|| (action.id.clone(), preconditions))
+            })
+            .collect(),
+    }
+}
@@ -1,43 +1,153 @@
-use std::collections::VecDeque;
+use std::time::Duration;
 
-use bevy::prelude::{Commands, Component, Entity, EventReader, Query};
-use pathfinding::prelude::astar;
+use bevy::{
+    ecs::{
+        entity::{EntityMap, MapEntities, MapEntitiesError},
+        reflect::ReflectMapEntities,
+    },
+    log::{debug, info, info_span},
+    prelude::{
+        Changed, Commands, Component, DetectChanges, Entity, EventReader, EventWriter, Query,
+        Reflect, ReflectComponent, Res, ResMut, Time,
+    },
+    utils::{
+        tracing::{field, Span},
+        HashMap, HashSet, Instant,
+    },
+};
+
+#[cfg(feature = "serde")]
+use bevy::reflect::{ReflectDeserialize, ReflectSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use crate::action::EvaluationResult;
-use crate::actor::ActorState;
-use crate::planning::plan_node::{PlanNode, PlanNodeId};
+use crate::actor::{ActorState, Plan};
+use crate::analysis::NoPlanExplanation;
+use crate::compound::cancel_sub_plans;
+use crate::events::{ActionStarted, LifecycleEvents, PlanCreated, PlanFailed, PlanFailureReason};
+use crate::history::{PlanHistory, PlanOutcome};
+use crate::metrics::GoapMetrics;
+use crate::planner::{self, heuristic};
+use crate::planning::search_tree::SearchTreeRecorder;
+use crate::settings::{GoapLogLevel, GoapLogSettings, PlannerSettings};
 use crate::{
-    action::{Action, ActionState},
+    action::{Action, ActionState, ActionStateQuery},
     actor::Actor,
+    state::GoapState,
 };
 
-mod plan_node;
+mod search_tree;
+
+pub use search_tree::{SearchTree, SearchTreeNode};
 
 pub struct RequestPlanEvent(pub(crate) Entity);
 
-#[derive(Component, Default, Debug)]
+/// The queue of `Actor`s awaiting a plan or a check for a cheaper plan. The plan cache is not
+/// reflected or serialized.
+#[derive(Component, Reflect, Clone, Default, Debug)]
+#[cfg_attr(not(feature = "serde"), reflect_value(Component, MapEntities))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    reflect_value(Component, MapEntities, Serialize, Deserialize)
+)]
 pub struct PlanningState {
     queue: Vec<Entity>,
+    /// `Actor`s to check for a plan cheaper than their current plan, see
+    /// `PlannerSettings::replan_on_change`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    checks: Vec<Entity>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    cache: HashMap<PlanCacheKey, Option<Plan>>,
+}
+
+impl PlanningState {
+    #[cfg(feature = "assets")]
+    pub(crate) fn clear_cache(&mut self) {
+        self.cache.clear();
+    }
+}
+
+impl MapEntities for PlanningState {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        for entity in self.queue.iter_mut().chain(self.checks.iter_mut()) {
+            *entity = entity_map.get(*entity)?;
+        }
+
+        // Cached plans refer to entities that may not have been mapped.
+        self.cache.clear();
+
+        Ok(())
+    }
+}
+
+/// Everything that determines the plan found by the planner, for a given `PlannerSettings`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct PlanCacheKey {
+    start: GoapState,
+    goal: GoapState,
+    actions: Vec<(Entity, i32)>,
 }
 
 pub fn create_planning_state(mut commands: Commands) {
     commands.spawn_empty().insert(PlanningState::default());
 }
 
+/// Merges the queues of any `PlanningState`s added after startup, e.g. by loading a scene, into the
+/// first `PlanningState`, and despawns the others.
+pub fn merge_planning_states_system(
+    mut commands: Commands,
+    mut planning_state_query: Query<(Entity, &mut PlanningState)>,
+) {
+    let mut planning_states = planning_state_query.iter_mut();
+
+    let Some((_, mut planning_state)) = planning_states.next() else {
+        return;
+    };
+
+    for (entity, mut other) in planning_states {
+        for actor_entity in std::mem::take(&mut other.queue) {
+            if !planning_state.queue.contains(&actor_entity) {
+                planning_state.queue.push(actor_entity);
+            }
+        }
+
+        for actor_entity in std::mem::take(&mut other.checks) {
+            if !planning_state.checks.contains(&actor_entity) {
+                planning_state.checks.push(actor_entity);
+            }
+        }
+
+        commands.entity(entity).despawn();
+    }
+}
+
 pub fn request_plan_event_handler_system(
     mut ev_request_plan: EventReader<RequestPlanEvent>,
     mut planning_state_query: Query<&mut PlanningState>,
-    actors_query: Query<&Actor>,
-    mut action_states_query: Query<&mut ActionState>,
+    mut actors_query: Query<&mut Actor>,
+    mut action_states_query: Query<ActionStateQuery>,
     actions_query: Query<&Action>,
+    log: Res<GoapLogSettings>,
 ) {
-    let mut planning_state = planning_state_query.single_mut();
+    // Until `merge_planning_states_system` runs, there may be more than one `PlanningState`.
+    let Some(mut planning_state) = planning_state_query.iter_mut().next() else {
+        return;
+    };
 
     for ev in ev_request_plan.iter() {
-        println!("Received RequestPlanEvent");
+        if log.planning >= GoapLogLevel::Verbose {
+            debug!(actor = ?ev.0, "Plan request received");
+        }
+
         let mut should_queue = false;
 
-        if let Ok(actor) = actors_query.get(ev.0) {
+        if let Ok(mut actor) = actors_query.get_mut(ev.0) {
+            // The sub-actions of an executing CompoundAction are not Actions of the Actor, so they
+            // must be stopped here.
+            cancel_sub_plans(&mut actor, &mut action_states_query);
+
             for action_entity in actor.actions.iter() {
                 let action = actions_query.get(*action_entity).unwrap();
                 let mut action_state = action_states_query.get_mut(*action_entity).unwrap();
@@ -59,48 +169,98 @@ pub fn request_plan_event_handler_system(
                 }
 
                 if action_postconditions_already_satisfied {
-                    *action_state = ActionState::EvaluationComplete(EvaluationResult::Skipped);
+                    action_state.issue(ActionState::EvaluationComplete(EvaluationResult::Skipped));
                 } else {
                     // Since we have found at least one action that can be in the plan, we can queue this request.
                     should_queue = true;
-                    *action_state = ActionState::Evaluate;
+                    action_state.issue(ActionState::Evaluate);
                 }
             }
         }
 
         if should_queue {
-            println!("Pushing {:?} to queue", ev.0);
-            planning_state.queue.push(ev.0);
+            if log.planning >= GoapLogLevel::Verbose {
+                debug!(actor = ?ev.0, "Plan request queued");
+            }
+
+            if !planning_state.queue.contains(&ev.0) {
+                planning_state.queue.push(ev.0);
+            }
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_plan_system(
+    mut commands: Commands,
     mut planning_state_query: Query<&mut PlanningState>,
     mut actors: Query<&mut Actor>,
-    mut action_states: Query<&mut ActionState>,
+    mut action_states: Query<ActionStateQuery>,
     mut actor_states: Query<&mut ActorState>,
     actions: Query<&Action>,
+    planner_settings: Res<PlannerSettings>,
+    log: Res<GoapLogSettings>,
+    mut events: LifecycleEvents,
+    mut metrics: ResMut<GoapMetrics>,
+    mut histories: Query<&mut PlanHistory>,
+    time: Option<Res<Time>>,
 ) {
+    let Some(mut planning_state) = planning_state_query.iter_mut().next() else {
+        return;
+    };
     let mut new_queue: Vec<Entity> = vec![];
+    let mut plans_created = 0;
 
-    for actor_entity in planning_state_query.single().queue.iter() {
-        println!("Plan requested for {:?}", actor_entity);
+    if planner_settings.is_changed() {
+        // Cached plans may no longer be the plans the planner would find.
+        planning_state.cache.clear();
+    }
+
+    for actor_entity in std::mem::take(&mut planning_state.queue).iter() {
+        let span = if log.planning >= GoapLogLevel::Info {
+            info_span!(
+                "create_plan",
+                actor = ?actor_entity,
+                nodes_expanded = field::Empty,
+                duration_us = field::Empty
+            )
+        } else {
+            Span::none()
+        };
+        let _entered = span.enter();
+
+        if log.planning >= GoapLogLevel::Verbose {
+            debug!("Planning");
+        }
+
+        if planner_settings
+            .max_plans_per_frame
+            .is_some_and(|max_plans| plans_created >= max_plans)
+        {
+            // The planning budget for this frame has been spent, so we must requeue the plan
+            // request for this actor to plan it later.
+            metrics.requeues_plan_budget += 1;
+            new_queue.push(*actor_entity);
+            continue;
+        }
 
         if let Ok(mut actor) = actors.get_mut(*actor_entity) {
             let all_actions_ready = actor.actions.iter().all(|action_entity| {
                 matches!(
-                    action_states.get(*action_entity),
+                    action_states
+                        .get(*action_entity)
+                        .map(|action_state| action_state.get()),
                     Ok(ActionState::EvaluationComplete(_))
                 )
             });
 
             if !all_actions_ready {
-                println!(
-                    "Not all actions are ready for {:?}, re-queueing request",
-                    actor_entity
-                );
+                if log.planning >= GoapLogLevel::Verbose {
+                    debug!("Not all actions are evaluated, re-queueing request");
+                }
+
                 // Not all the actions for this actor have finished evaluating, we must requeue the plan request for this actor to plan it later.
+                metrics.requeues_pending_evaluation += 1;
                 new_queue.push(*actor_entity);
                 continue;
             }
@@ -108,44 +268,195 @@ pub fn create_plan_system(
             let actor_actions = actor
                 .actions
                 .iter()
-                .filter_map(|action_entity| match action_states.get(*action_entity) {
-                    // Only consider actions that have a succesful evaluation.
-                    Ok(ActionState::EvaluationComplete(EvaluationResult::Success)) => {
-                        let action = actions.get(*action_entity).unwrap();
+                .filter_map(|action_entity| {
+                    match action_states
+                        .get(*action_entity)
+                        .map(|action_state| action_state.get())
+                    {
+                        // Only consider actions that have a succesful evaluation.
+                        Ok(ActionState::EvaluationComplete(EvaluationResult::Success)) => {
+                            let action = actions.get(*action_entity).unwrap();
 
-                        Some((action, action_entity))
+                            Some((action, action_entity))
+                        }
+                        _ => None,
                     }
-                    _ => None,
                 })
                 .collect::<Vec<_>>();
 
-            let start_node = PlanNode::get_initial(&actor.current_state);
-
-            let (node_path, _) = astar(
-                &start_node,
-                |node| node.get_successors(&actor_actions),
-                |node| node.mismatch_count(&actor.current_goal),
-                |node| node.matches(&actor.current_goal),
-            )
-            .unwrap_or((vec![], 0));
+            plans_created += 1;
 
-            let action_path = node_path.iter().filter_map(|node| match node.id {
-                PlanNodeId::Action(e) => Some(e),
-                _ => None,
+            let cache_key = planner_settings.plan_cache_size.map(|_| PlanCacheKey {
+                start: actor.current_state.clone(),
+                goal: actor.current_goal.clone(),
+                actions: actor_actions
+                    .iter()
+                    .map(|(action, action_entity)| (**action_entity, action.cost))
+                    .collect(),
             });
 
-            actor.current_path = VecDeque::from_iter(action_path);
+            let cached_plan = cache_key
+                .as_ref()
+                .and_then(|key| planning_state.cache.get(key).cloned());
+
+            let plan = if let Some(plan) = cached_plan {
+                metrics.cache_hits += 1;
+
+                if log.planning >= GoapLogLevel::Verbose {
+                    debug!("Plan cache hit");
+                }
+
+                plan
+            } else {
+                let started_at = Instant::now();
+
+                let planner_actions = actor_actions
+                    .iter()
+                    .map(|(action, action_entity)| action.planner_action(**action_entity))
+                    .collect::<Vec<_>>();
+                let options = planner_settings.search_options();
+                let goal = &actor.current_goal.state;
+
+                // A repair keeps the still-valid suffix of the previous plan, e.g. after a step
+                // failed, and only searches for a bridge to it.
+                let repair = (planner_settings.repair_plans
+                    && !actor.skip_repair
+                    && !actor.current_path.is_empty())
+                .then(|| {
+                    planner::repair(
+                        &actor.current_state.state,
+                        goal,
+                        &actor.current_path.iter().copied().collect::<Vec<_>>(),
+                        &planner_actions,
+                        &options,
+                    )
+                });
+
+                let (result, repaired) = match repair {
+                    Some(result) if result.plan.is_some() => {
+                        metrics.plans_repaired += 1;
+
+                        if log.planning >= GoapLogLevel::Verbose {
+                            debug!("Plan repaired");
+                        }
+
+                        // The SearchTree of a previous search does not explain the repaired plan.
+                        commands.entity(*actor_entity).remove::<SearchTree>();
+
+                        (result, true)
+                    }
+                    repair => {
+                        if repair.is_some() {
+                            metrics.failed_repairs += 1;
+
+                            if log.planning >= GoapLogLevel::Verbose {
+                                debug!("No plan repair available, searching for a new plan");
+                            }
+                        }
+
+                        let mut recorder = planner_settings.record_search_tree.then(|| {
+                            SearchTreeRecorder::new(
+                                &actor.current_state.state,
+                                heuristic(&actor.current_state.state, goal, options.strategy),
+                            )
+                        });
+
+                        let mut result = planner::search(
+                            &actor.current_state.state,
+                            goal,
+                            &planner_actions,
+                            &options,
+                            |node, successors| {
+                                if let Some(recorder) = recorder.as_mut() {
+                                    recorder.expand(
+                                        node,
+                                        successors,
+                                        |state| heuristic(state, goal, options.strategy),
+                                        |action_entity| actions.get(action_entity).unwrap().name,
+                                    );
+                                }
+                            },
+                        );
+
+                        if let Some(recorder) = recorder {
+                            commands
+                                .entity(*actor_entity)
+                                .insert(recorder.finish(&result.path));
+                        }
+
+                        result.expanded_nodes += repair.map_or(0, |repair| repair.expanded_nodes);
+
+                        (result, false)
+                    }
+                };
+
+                let search_time = started_at.elapsed();
+                metrics.record_search(result.expanded_nodes, search_time);
+                span.record("nodes_expanded", result.expanded_nodes);
+                span.record("duration_us", search_time.as_micros() as u64);
+
+                let plan = result.plan.map(|plan| Plan {
+                    steps: plan.actions,
+                    cost: plan.cost,
+                    expected_state: GoapState {
+                        state: plan.end_state,
+                    },
+                });
+
+                if let (Some(cache_key), Some(cache_size)) =
+                    (cache_key, planner_settings.plan_cache_size)
+                {
+                    metrics.cache_misses += 1;
+
+                    if planning_state.cache.len() >= cache_size {
+                        planning_state.cache.clear();
+                    }
+
+                    // A repaired plan depends on the previous plan of the Actor, which is not part
+                    // of the cache key, so it is not cached. A cache hit is used instead of a
+                    // repair, as it is the plan a full search would find.
+                    if !repaired {
+                        planning_state.cache.insert(cache_key, plan.clone());
+                    }
+                }
+
+                plan
+            };
+
+            let cost = plan.as_ref().map_or(0, |plan| plan.cost);
+
+            if let Ok(mut history) = histories.get_mut(*actor_entity) {
+                history.finish(PlanOutcome::Interrupted {
+                    step: actor.current_step().unwrap_or_default(),
+                });
+                history.push(
+                    time.as_ref().map_or(Duration::ZERO, |time| time.elapsed()),
+                    &actor.current_goal,
+                    &actor.current_state,
+                    plan.iter()
+                        .flat_map(|plan| plan.steps.iter())
+                        .map(|step| actions.get(*step).unwrap().name.to_string())
+                        .collect(),
+                    cost,
+                );
+            }
 
-            if let Some(first_action_entity) = actor.current_path.front() {
-                println!("Plan created for {:?}.", actor_entity);
+            actor.set_plan(plan);
+
+            if !actor.current_path.is_empty() {
+                if log.planning >= GoapLogLevel::Info {
+                    info!(steps = actor.current_path.len(), cost, "Plan created");
+                }
+
+                let started_actions = actor.startable_steps(&actions);
 
                 for action_entity in actor.actions.iter() {
                     let mut action_state = action_states.get_mut(*action_entity).unwrap();
 
-                    if action_entity == first_action_entity {
-                        *action_state = ActionState::Started;
+                    if started_actions.contains(action_entity) {
+                        action_state.issue(ActionState::Started);
                     } else if actor.current_path.contains(action_entity) {
-                        *action_state = ActionState::WaitingToStart;
+                        action_state.issue(ActionState::WaitingToStart);
                     } else {
                         action_state.mark_not_in_plan();
                     }
@@ -153,8 +464,49 @@ pub fn create_plan_system(
 
                 let mut actor_state = actor_states.get_mut(*actor_entity).unwrap();
                 *actor_state = ActorState::ExecutingPlan;
+
+                commands.entity(*actor_entity).remove::<NoPlanExplanation>();
+
+                events.plan_created.send(PlanCreated {
+                    actor_entity: *actor_entity,
+                    steps: actor.current_path.iter().copied().collect(),
+                    cost,
+                });
+
+                for action_entity in started_actions {
+                    actor.start_action(action_entity);
+
+                    events.action_started.send(ActionStarted {
+                        actor_entity: *actor_entity,
+                        action_entity,
+                    });
+                }
             } else {
-                println!("No plan available for {:?}.", actor_entity);
+                if log.planning >= GoapLogLevel::Info {
+                    info!("No plan available");
+                }
+
+                let evaluated_actions = actor
+                    .actions
+                    .iter()
+                    .filter_map(|action_entity| match action_states.get(*action_entity) {
+                        Ok(action_state) => match action_state.get() {
+                            ActionState::EvaluationComplete(result) => {
+                                Some((*action_entity, actions.get(*action_entity).unwrap(), result))
+                            }
+                            _ => None,
+                        },
+                        Err(_) => None,
+                    })
+                    .collect::<Vec<_>>();
+
+                commands
+                    .entity(*actor_entity)
+                    .insert(NoPlanExplanation::explain(
+                        &actor.current_state,
+                        &actor.current_goal,
+                        &evaluated_actions,
+                    ));
 
                 for action_entity in actor.actions.iter() {
                     let mut action_state = action_states.get_mut(*action_entity).unwrap();
@@ -163,9 +515,211 @@ pub fn create_plan_system(
 
                 let mut actor_state = actor_states.get_mut(*actor_entity).unwrap();
                 *actor_state = ActorState::NoPlanAvailable;
+
+                events.plan_failed.send(PlanFailed {
+                    actor_entity: *actor_entity,
+                    reason: PlanFailureReason::NoPlanAvailable,
+                });
             }
         }
     }
 
-    planning_state_query.single_mut().queue = new_queue;
+    let mut new_checks = vec![];
+
+    for actor_entity in std::mem::take(&mut planning_state.checks) {
+        if planner_settings
+            .max_plans_per_frame
+            .is_some_and(|max_plans| plans_created >= max_plans)
+        {
+            metrics.requeues_plan_budget += 1;
+            new_checks.push(actor_entity);
+            continue;
+        }
+
+        let (Ok(mut actor), Ok(mut actor_state)) = (
+            actors.get_mut(actor_entity),
+            actor_states.get_mut(actor_entity),
+        ) else {
+            continue;
+        };
+
+        // The Actor may have replanned or stopped its plan since the check was queued.
+        if *actor_state != ActorState::ExecutingPlan {
+            continue;
+        }
+
+        plans_created += 1;
+
+        check_for_cheaper_plan(
+            actor_entity,
+            &mut actor,
+            &mut actor_state,
+            &actions,
+            &planner_settings,
+            &log,
+            &mut metrics,
+        );
+    }
+
+    metrics.plans_last_frame = plans_created;
+    metrics.queue_length = new_queue.len();
+
+    planning_state.queue = new_queue;
+    planning_state.checks = new_checks;
+}
+
+/// Searches for a plan from the current state of the `Actor`, and replans if it is cheaper than the
+/// remaining steps of the current plan.
+fn check_for_cheaper_plan(
+    actor_entity: Entity,
+    actor: &mut Actor,
+    actor_state: &mut ActorState,
+    actions: &Query<&Action>,
+    planner_settings: &PlannerSettings,
+    log: &GoapLogSettings,
+    metrics: &mut GoapMetrics,
+) {
+    let remaining_cost = actions
+        .iter_many(&actor.current_path)
+        .map(|action| action.cost)
+        .sum::<i32>();
+
+    // Actions are not evaluated here, so they are considered with their current cost.
+    let planner_actions = actor
+        .actions
+        .iter()
+        .filter_map(|action_entity| {
+            Some(
+                actions
+                    .get(*action_entity)
+                    .ok()?
+                    .planner_action(*action_entity),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let started_at = Instant::now();
+
+    let result = planner::search(
+        &actor.current_state.state,
+        &actor.current_goal.state,
+        &planner_actions,
+        &planner_settings.search_options(),
+        |_, _| {},
+    );
+
+    metrics.record_search(result.expanded_nodes, started_at.elapsed());
+
+    if result.plan.is_some_and(|plan| plan.cost < remaining_cost) {
+        if log.planning >= GoapLogLevel::Info {
+            info!(actor = ?actor_entity, "A cheaper plan is available");
+        }
+
+        // Repairing would keep the remaining steps of the current plan, which are still valid.
+        actor.skip_repair = true;
+        *actor_state = ActorState::RequiresPlan;
+    } else if log.planning >= GoapLogLevel::Verbose {
+        debug!(actor = ?actor_entity, "The current plan is still the cheapest");
+    }
+}
+
+/// Records that the plan of `actor` was invalidated by a changed current state, before it replans.
+pub(crate) fn record_invalidated_plan(
+    actor_entity: Entity,
+    actor: &Actor,
+    actions: &Query<&Action>,
+    histories: &mut Query<&mut PlanHistory>,
+    ev_plan_failed: &mut EventWriter<PlanFailed>,
+) {
+    if let Ok(mut history) = histories.get_mut(actor_entity) {
+        history.finish(PlanOutcome::Failed {
+            step: actor.current_step().unwrap_or_default(),
+            action: actor
+                .current_path
+                .front()
+                .and_then(|step| actions.get(*step).ok())
+                .map_or_else(String::new, |action| action.name.to_string()),
+            reason: PlanFailureReason::PlanInvalidated,
+        });
+    }
+
+    ev_plan_failed.send(PlanFailed {
+        actor_entity,
+        reason: PlanFailureReason::PlanInvalidated,
+    });
+}
+
+/// Checks the plan of every `Actor` executing a plan when a `Condition` its plan depends on has
+/// changed other than by the effects of the plan, see `PlannerSettings::replan_on_change`. The
+/// `Actor` replans if its plan can no longer reach the goal, and is otherwise queued to check for
+/// a cheaper plan in `create_plan_system`.
+pub fn replan_on_change_system(
+    mut actors: Query<(Entity, &mut Actor, &mut ActorState), Changed<Actor>>,
+    actions: Query<&Action>,
+    mut planning_state_query: Query<&mut PlanningState>,
+    mut histories: Query<&mut PlanHistory>,
+    mut ev_plan_failed: EventWriter<PlanFailed>,
+    planner_settings: Res<PlannerSettings>,
+    log: Res<GoapLogSettings>,
+) {
+    if !planner_settings.replan_on_change {
+        return;
+    }
+
+    // Until `merge_planning_states_system` runs, there may be more than one `PlanningState`.
+    let Some(mut planning_state) = planning_state_query.iter_mut().next() else {
+        return;
+    };
+
+    for (actor_entity, mut actor, mut actor_state) in actors.iter_mut() {
+        let Some(observed_state) = actor.observed_state.as_ref() else {
+            continue;
+        };
+
+        if *actor_state != ActorState::ExecutingPlan || *observed_state == actor.current_state {
+            continue;
+        }
+
+        let changed_conditions = actor
+            .current_path
+            .iter()
+            .filter_map(|step| actions.get(*step).ok())
+            .flat_map(|action| action.preconditions.entries())
+            .chain(actor.current_goal.entries())
+            .filter(|(condition, _)| {
+                observed_state.state.get(condition) != actor.current_state.state.get(condition)
+            })
+            .map(|(condition, _)| condition.name())
+            .collect::<HashSet<_>>();
+
+        actor.bypass_change_detection().observed_state = Some(actor.current_state.clone());
+
+        if changed_conditions.is_empty() {
+            continue;
+        }
+
+        if !actor.is_plan_valid(&actions) {
+            if log.planning >= GoapLogLevel::Info {
+                info!(actor = ?actor_entity, conditions = ?changed_conditions, "Changed conditions invalidated the plan");
+            }
+
+            record_invalidated_plan(
+                actor_entity,
+                &actor,
+                &actions,
+                &mut histories,
+                &mut ev_plan_failed,
+            );
+            *actor_state = ActorState::RequiresPlan;
+            continue;
+        }
+
+        if log.planning >= GoapLogLevel::Verbose {
+            debug!(actor = ?actor_entity, conditions = ?changed_conditions, "Changed conditions may have made a cheaper plan available");
+        }
+
+        if !planning_state.checks.contains(&actor_entity) {
+            planning_state.checks.push(actor_entity);
+        }
+    }
 }
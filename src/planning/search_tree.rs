@@ -0,0 +1,166 @@
+use std::fmt::Write;
+
+use bevy::{
+    prelude::{Component, Entity},
+    utils::HashMap,
+};
+
+use crate::{
+    dot::{escape, format_state},
+    planner::{SearchNode, State},
+    state::ConditionId,
+};
+
+type Node = SearchNode<ConditionId, Entity>;
+
+/// A node explored by the planner.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SearchTreeNode {
+    /// The name of the `Action` leading to this node, or `None` for the start node.
+    pub action: Option<&'static str>,
+    /// The name and value of each `Condition` in the state of this node.
+    pub state: Vec<(&'static str, bool)>,
+    /// The lowest known cost to reach this node.
+    pub g: i32,
+    /// The heuristic estimate of the cost from this node to the goal.
+    pub h: i32,
+    /// Whether the planner expanded this node, rather than only generating it as a successor.
+    pub expanded: bool,
+    /// Whether this node is on the chosen plan.
+    pub on_path: bool,
+}
+
+impl SearchTreeNode {
+    /// The estimated total cost of a plan through this node.
+    pub fn f(&self) -> i32 {
+        self.g + self.h
+    }
+}
+
+/// The nodes explored by the planner during the last search for an `Actor`, inserted on the
+/// `Actor`'s entity when `PlannerSettings::record_search_tree` is enabled.
+#[derive(Component, Clone, PartialEq, Eq, Debug, Default)]
+pub struct SearchTree {
+    pub nodes: Vec<SearchTreeNode>,
+    /// The index of the parent node, the index of the successor node, and the cost of the `Action`
+    /// between them.
+    pub edges: Vec<(usize, usize, i32)>,
+}
+
+impl SearchTree {
+    /// Exports this search tree as a Graphviz DOT graph, highlighting the chosen plan.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph search {\n    node [shape=box];\n");
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let label = format!(
+                "{}\\nf={} g={} h={}\\n{}",
+                escape(node.action.unwrap_or("Start")),
+                node.f(),
+                node.g,
+                node.h,
+                escape(&format_state(node.state.iter().copied()))
+            );
+            let style = match (node.on_path, node.expanded) {
+                (true, _) => ", style=\"bold,filled\", fillcolor=palegreen",
+                (false, true) => "",
+                (false, false) => ", style=dashed",
+            };
+
+            writeln!(dot, "    n{index} [label=\"{label}\"{style}];").unwrap();
+        }
+
+        for (from, to, cost) in self.edges.iter() {
+            let style = if self.nodes[*from].on_path && self.nodes[*to].on_path {
+                ", penwidth=3, color=green"
+            } else {
+                ""
+            };
+
+            writeln!(dot, "    n{from} -> n{to} [label=\"{cost}\"{style}];").unwrap();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Records the `SearchTree` while the planner searches.
+pub(crate) struct SearchTreeRecorder {
+    indices: HashMap<Node, usize>,
+    tree: SearchTree,
+}
+
+impl SearchTreeRecorder {
+    pub(crate) fn new(start: &State<ConditionId>, h: i32) -> Self {
+        let mut recorder = Self {
+            indices: HashMap::default(),
+            tree: SearchTree::default(),
+        };
+        let start = SearchNode {
+            action: None,
+            state: start.clone(),
+        };
+        recorder.node_index(&start, None, 0, h);
+        recorder
+    }
+
+    pub(crate) fn expand(
+        &mut self,
+        node: &Node,
+        successors: &[(Node, i32)],
+        h: impl Fn(&State<ConditionId>) -> i32,
+        action_name: impl Fn(Entity) -> &'static str,
+    ) {
+        let Some(&parent) = self.indices.get(node) else {
+            return;
+        };
+
+        self.tree.nodes[parent].expanded = true;
+        let parent_g = self.tree.nodes[parent].g;
+
+        for (successor, cost) in successors {
+            let action = successor.action.map(&action_name);
+            let child = self.node_index(successor, action, parent_g + cost, h(&successor.state));
+
+            self.tree.edges.push((parent, child, *cost));
+        }
+    }
+
+    pub(crate) fn finish(mut self, path: &[Node]) -> SearchTree {
+        for node in path {
+            if let Some(&index) = self.indices.get(node) {
+                self.tree.nodes[index].on_path = true;
+            }
+        }
+
+        self.tree
+    }
+
+    fn node_index(&mut self, node: &Node, action: Option<&'static str>, g: i32, h: i32) -> usize {
+        if let Some(&index) = self.indices.get(node) {
+            let existing = &mut self.tree.nodes[index];
+            existing.g = existing.g.min(g);
+            return index;
+        }
+
+        let mut state = node
+            .state
+            .iter()
+            .map(|(condition, value)| (condition.name(), value))
+            .collect::<Vec<_>>();
+        state.sort();
+
+        let index = self.tree.nodes.len();
+        self.tree.nodes.push(SearchTreeNode {
+            action,
+            state,
+            g,
+            h,
+            expanded: false,
+            on_path: false,
+        });
+        self.indices.insert(node.clone(), index);
+        index
+    }
+}
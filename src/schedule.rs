@@ -0,0 +1,57 @@
+use bevy::{
+    ecs::schedule::{ShouldRun, StageLabelId, StateData},
+    prelude::{CoreStage, Res, StageLabel, State},
+};
+
+/// Where the crate's systems run.
+#[derive(Clone, Copy, Debug)]
+pub enum GoapSchedule {
+    /// The crate's systems run in the app's main schedule, with the `GoapStage`s inserted after
+    /// `goap_stages_after`.
+    Main {
+        /// The stage in which new `Actor`s and their `Action`s are built.
+        build_stage: StageLabelId,
+        /// The stage after which the `GoapStage`s are inserted.
+        goap_stages_after: StageLabelId,
+        /// The stage in which plans are created.
+        planning_stage: StageLabelId,
+    },
+    /// All of the crate's systems run in their own `Schedule`, inserted as a stage labelled
+    /// `GoapScheduleLabel` after the given stage.
+    ///
+    /// A run criteria, e.g. a `FixedTimestep` or `run_in_state`, can be set with
+    /// `GoapAppExt::set_goap_run_criteria`, and user systems should be added with
+    /// `GoapAppExt::add_goap_system`.
+    Nested { after: StageLabelId },
+    /// The plugin does not add any of the crate's systems. Use `GoapPlugin::create_schedule` to add
+    /// them to your own schedule.
+    Manual,
+}
+
+impl Default for GoapSchedule {
+    fn default() -> Self {
+        GoapSchedule::Main {
+            build_stage: CoreStage::First.as_label(),
+            // User Action systems can then check for progress after typical user systems (e.g. for
+            // movement) complete during Update.
+            goap_stages_after: CoreStage::Update.as_label(),
+            planning_stage: CoreStage::Last.as_label(),
+        }
+    }
+}
+
+/// The label of the stage containing the crate's `Schedule`, when using `GoapSchedule::Nested`.
+#[derive(StageLabel)]
+pub struct GoapScheduleLabel;
+
+/// A run criteria that only runs the crate's systems while the Bevy `State` `S` is `state`, e.g. to
+/// pause all `Actor`s while the game is paused.
+pub fn run_in_state<S: StateData>(state: S) -> impl FnMut(Res<State<S>>) -> ShouldRun {
+    move |current_state: Res<State<S>>| {
+        if *current_state.current() == state {
+            ShouldRun::Yes
+        } else {
+            ShouldRun::No
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use bevy::{
+    ecs::system::{StaticSystemParam, SystemParam, SystemParamItem},
+    log::{debug, info},
+    prelude::{Entity, EventWriter, Query, Res},
+};
+
+use crate::{
+    action::Action,
+    actor::{Actor, ActorState},
+    events::PlanFailed,
+    history::PlanHistory,
+    planning::record_invalidated_plan,
+    settings::{GoapLogLevel, GoapLogSettings},
+    Condition,
+};
+
+/// When a `Sensor` updates the current state of each `Actor`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SensorUpdate {
+    /// Only when the `Actor` is in `ActorState::RequiresPlan`, so that the next plan starts from
+    /// the sensed value.
+    BeforePlanning,
+    /// Every update. If a sensed value changes while the `Actor` is executing a plan, and the
+    /// remaining steps of the plan can no longer be executed or no longer reach the goal, the
+    /// `Actor` transitions to `ActorState::RequiresPlan`.
+    EveryUpdate,
+}
+
+/// A trait for `Condition`s whose value can be derived from the world, e.g. `HasAxe` when the
+/// `Actor` has an `Inventory` containing an axe, so that the current state of each `Actor` is kept
+/// in sync rather than updated by hand with `Actor::update_current_state`.
+///
+/// Register an implementation with `GoapAppExt::add_goap_sensor`. The `Sensor` runs after user
+/// `Actor` systems and before the crate reacts to changed `ActorState`s.
+/// `SensorUpdate::EveryUpdate` `Sensor`s run in `GoapStage::Sensors`, and
+/// `SensorUpdate::BeforePlanning` `Sensor`s after them, so that they also see `Actor`s that an
+/// `EveryUpdate` `Sensor` transitioned to `ActorState::RequiresPlan`.
+///
+/// `sense` is given the `SystemParam` declared by `Param`, which must not access the `Actor`,
+/// `ActorState`, `Action` or `PlanHistory` components already accessed by the sensing system.
+pub trait Sensor: Condition + Send + Sync + 'static {
+    /// The system parameters required by `sense`, e.g. `SQuery<Read<Inventory>>` using the types in
+    /// `bevy::ecs::system::lifetimeless`.
+    type Param: SystemParam + 'static;
+
+    /// When this `Sensor` updates the current state of each `Actor`. Defaults to
+    /// `SensorUpdate::BeforePlanning`.
+    const UPDATE: SensorUpdate = SensorUpdate::BeforePlanning;
+
+    /// Derives the value of this `Condition` for the `Actor` on `actor_entity`, or returns `None`
+    /// to leave it unchanged, e.g. for an `Actor` without an `Inventory`.
+    fn sense(actor_entity: Entity, param: &mut SystemParamItem<Self::Param>) -> Option<bool>;
+}
+
+/// Updates the current state of every `Actor` with the `Sensor` `T`.
+pub(crate) fn sensor_system<T: Sensor>(
+    mut actors: Query<(Entity, &mut Actor, &mut ActorState)>,
+    actions: Query<&Action>,
+    mut histories: Query<&mut PlanHistory>,
+    mut ev_plan_failed: EventWriter<PlanFailed>,
+    param: StaticSystemParam<T::Param>,
+    log: Res<GoapLogSettings>,
+) {
+    let mut param = param.into_inner();
+
+    for (actor_entity, mut actor, mut actor_state) in actors.iter_mut() {
+        if T::UPDATE == SensorUpdate::BeforePlanning && *actor_state != ActorState::RequiresPlan {
+            continue;
+        }
+
+        let Some(value) = T::sense(actor_entity, &mut param) else {
+            continue;
+        };
+
+        // Avoid triggering change detection when the sensed value is unchanged.
+        if actor.current_state.get::<T>() == Some(value) {
+            continue;
+        }
+
+        actor.current_state.insert::<T>(value);
+
+        if log.actors >= GoapLogLevel::Verbose {
+            debug!(actor = ?actor_entity, condition = T::name(), value, "Condition sensed");
+        }
+
+        if *actor_state == ActorState::ExecutingPlan && !actor.is_plan_valid(&actions) {
+            if log.actors >= GoapLogLevel::Info {
+                info!(actor = ?actor_entity, condition = T::name(), "Sensed condition invalidated the plan");
+            }
+
+            record_invalidated_plan(
+                actor_entity,
+                &actor,
+                &actions,
+                &mut histories,
+                &mut ev_plan_failed,
+            );
+
+            *actor_state = ActorState::RequiresPlan;
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use bevy::prelude::Resource;
+
+pub use crate::planner::PlannerStrategy;
+use crate::planner::SearchOptions;
+
+/// Settings for the planner, which may be changed at runtime.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct PlannerSettings {
+    pub strategy: PlannerStrategy,
+    /// The maximum number of plans to create per frame. Any further plan requests remain queued
+    /// until the next frame.
+    pub max_plans_per_frame: Option<usize>,
+    /// The maximum number of nodes the planner may expand when searching for a single plan. If
+    /// exceeded, no plan is available.
+    pub max_expanded_nodes: Option<usize>,
+    /// The maximum number of plans to cache. A cached plan is reused when an `Actor` plans again
+    /// from the same state, towards the same goal, with the same evaluated `Action`s and costs.
+    /// Once full, the cache is cleared. Disabled by default.
+    pub plan_cache_size: Option<usize>,
+    /// Whether to record the nodes explored by each search as a `SearchTree` on the `Actor`'s
+    /// entity, e.g. to export it with `SearchTree::to_dot`. Disabled by default.
+    pub record_search_tree: bool,
+    /// Whether an `Actor` executing a plan replans when a `Condition` its plan depends on changes
+    /// other than by the plan's effects, e.g. by a `Sensor`, and the plan can no longer reach the
+    /// goal or a cheaper plan is available. Checks for a cheaper plan go through the planning queue
+    /// and count towards `max_plans_per_frame`. Disabled by default.
+    pub replan_on_change: bool,
+    /// Whether an `Actor` that replans while it still has remaining steps, e.g. after a step
+    /// failed, first tries to repair its plan, keeping a suffix of the remaining steps that can
+    /// still reach the goal and only searching for a bridge from the current state to it, see
+    /// `planner::repair`. An `Actor` replanning because a cheaper plan is available, see
+    /// `replan_on_change`, searches for a new plan instead. If no repair exists, a new plan is
+    /// searched for. This reduces the cost of replanning in large domains, but a repaired plan may
+    /// not be the cheapest plan. Disabled by default.
+    pub repair_plans: bool,
+}
+
+impl PlannerSettings {
+    /// The options of every search by the planner.
+    pub(crate) fn search_options(&self) -> SearchOptions {
+        SearchOptions {
+            strategy: self.strategy,
+            max_expanded_nodes: self.max_expanded_nodes,
+        }
+    }
+}
+
+/// The verbosity of the crate's logging for a subsystem.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+pub enum GoapLogLevel {
+    /// Nothing is logged.
+    #[default]
+    Off,
+    /// Outcomes, e.g. plans created and `Action`s completed, are logged as `info` events.
+    Info,
+    /// Every step is additionally logged as `debug` events.
+    Verbose,
+}
+
+/// The verbosity of the crate's logging per subsystem, which may be changed at runtime.
+///
+/// Logging uses `tracing` spans and events with structured fields, so it is output by Bevy's
+/// `LogPlugin` or any other subscriber, e.g. tracy. Each subsystem logs with its module as the
+/// target, i.e. `bevy_goap::planning`, `bevy_goap::action` and `bevy_goap::actor`, which may be
+/// used to filter the output further.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct GoapLogSettings {
+    /// Handling plan requests and creating plans.
+    pub planning: GoapLogLevel,
+    /// Transitions of `ActionState`s.
+    pub actions: GoapLogLevel,
+    /// Transitions of `ActorState`s and goals.
+    pub actors: GoapLogLevel,
+}
+
+impl GoapLogSettings {
+    /// Uses the same level for every subsystem.
+    pub fn all(level: GoapLogLevel) -> Self {
+        Self {
+            planning: level,
+            actions: level,
+            actors: level,
+        }
+    }
+}
+
+/// Default timeouts for `Action`s, which may be changed at runtime.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct TimeoutSettings {
+    /// The maximum duration an `Action` may remain in `ActionState::Evaluate`, before it is
+    /// considered to have failed its evaluation.
+    pub evaluation: Option<Duration>,
+    /// The maximum duration an `Action` may spend starting and executing, before it is considered
+    /// to have failed. This can be overridden per `Action` with `ActionBuilder::with_timeout`.
+    pub execution: Option<Duration>,
+}
@@ -0,0 +1,174 @@
+use std::fmt;
+
+use bevy::{
+    ecs::entity::{EntityMap, MapEntities, MapEntitiesError},
+    prelude::{Entity, World},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    action::{Action, ActionState, LastValidActionState},
+    actor::{Actor, ActorState},
+};
+
+/// The state of an `Action` captured in an `ActorSnapshot`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ActionSnapshot {
+    pub action_entity: Entity,
+    pub action: Action,
+    pub state: ActionState,
+}
+
+/// The state of an `Actor` and its `Action`s, including its current state, goal and plan progress,
+/// e.g. to be written to a save file.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ActorSnapshot {
+    pub actor_entity: Entity,
+    pub actor: Actor,
+    pub actor_state: ActorState,
+    /// The `Action`s of the `Actor`, in the order they were added to its `ActorBuilder`.
+    pub actions: Vec<ActionSnapshot>,
+}
+
+/// The reason an `ActorSnapshot` could not be restored.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The entity has no `Actor`, e.g. because its `ActorBuilder` has not been built yet.
+    ActorNotFound(Entity),
+    /// The `Actor` does not have the same `Action`s as the captured `Actor`.
+    MismatchedActions,
+    MapEntities(MapEntitiesError),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::ActorNotFound(entity) => write!(f, "no Actor found on {entity:?}"),
+            SnapshotError::MismatchedActions => {
+                write!(
+                    f,
+                    "the Actor does not have the same Actions as the snapshot"
+                )
+            }
+            SnapshotError::MapEntities(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<MapEntitiesError> for SnapshotError {
+    fn from(error: MapEntitiesError) -> Self {
+        SnapshotError::MapEntities(error)
+    }
+}
+
+impl ActorSnapshot {
+    /// Captures the `Actor` on `actor_entity` and its `Action`s. Returns `None` if the entity has
+    /// no `Actor`.
+    pub fn capture(world: &World, actor_entity: Entity) -> Option<Self> {
+        let actor = world.get::<Actor>(actor_entity)?;
+
+        let actions = actor
+            .actions
+            .iter()
+            .map(|action_entity| {
+                Some(ActionSnapshot {
+                    action_entity: *action_entity,
+                    action: world.get::<Action>(*action_entity)?.clone(),
+                    state: *world.get::<ActionState>(*action_entity)?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            actor_entity,
+            actor: actor.clone(),
+            actor_state: world.get::<ActorState>(actor_entity)?.clone(),
+            actions,
+        })
+    }
+
+    /// Restores this snapshot onto the `Actor` on `actor_entity`, which must have been built from
+    /// the same `ActorBuilder` as the captured `Actor`, e.g. after loading a save file. The
+    /// captured `Action`s are matched to the `Action`s of the `Actor` by their order, and every
+    /// entity reference is remapped to the new entities.
+    ///
+    /// An `Actor` captured while awaiting a plan requests a new plan, since pending plan requests
+    /// are not captured. Likewise, a `CompoundAction` captured while executing its sub-plan is
+    /// started again.
+    pub fn restore(&self, world: &mut World, actor_entity: Entity) -> Result<(), SnapshotError> {
+        let new_action_entities = world
+            .get::<Actor>(actor_entity)
+            .ok_or(SnapshotError::ActorNotFound(actor_entity))?
+            .actions
+            .clone();
+
+        let actions_match = new_action_entities.len() == self.actions.len()
+            && new_action_entities.iter().zip(self.actions.iter()).all(
+                |(new_action_entity, captured)| {
+                    world
+                        .get::<Action>(*new_action_entity)
+                        .is_some_and(|action| action.name == captured.action.name)
+                },
+            );
+
+        if !actions_match {
+            return Err(SnapshotError::MismatchedActions);
+        }
+
+        let mut entity_map = EntityMap::default();
+        entity_map.insert(self.actor_entity, actor_entity);
+
+        for (new_action_entity, captured) in new_action_entities.iter().zip(self.actions.iter()) {
+            entity_map.insert(captured.action_entity, *new_action_entity);
+        }
+
+        let awaiting_plan = self.actor_state == ActorState::AwaitingPlan;
+
+        let mut actor = self.actor.clone();
+
+        // Sub-actions are not captured, so their sub-plans cannot be restored.
+        let restarted_action = actor
+            .sub_plans
+            .first()
+            .map(|sub_plan| sub_plan.compound_action);
+        actor.sub_plans.clear();
+
+        actor.map_entities(&entity_map)?;
+
+        world
+            .entity_mut(actor_entity)
+            .insert(actor)
+            .insert(if awaiting_plan {
+                ActorState::RequiresPlan
+            } else {
+                self.actor_state.clone()
+            });
+
+        for (new_action_entity, captured) in new_action_entities.iter().zip(self.actions.iter()) {
+            let mut action = captured.action.clone();
+            action.map_entities(&entity_map)?;
+
+            let state = if awaiting_plan {
+                ActionState::Idle
+            } else if restarted_action == Some(captured.action_entity) {
+                ActionState::Started
+            } else {
+                captured.state
+            };
+
+            world
+                .entity_mut(*new_action_entity)
+                .insert(action)
+                .insert(state)
+                .insert(LastValidActionState(state));
+        }
+
+        Ok(())
+    }
+}
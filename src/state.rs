@@ -1,34 +1,220 @@
-use std::{any::TypeId, collections::HashMap, hash::Hash};
+use std::{
+    any::TypeId,
+    hash::{Hash, Hasher},
+};
+#[cfg(feature = "serde")]
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{OnceLock, RwLock},
+};
 
-#[derive(Debug, Clone, Eq)]
-pub struct GoapState {
-    pub(crate) state: HashMap<TypeId, bool>,
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{planner::State, Condition};
+
+/// Identifies a `Condition` by its type. The name is only used for display and serialization.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ConditionId {
+    key: ConditionKey,
+    name: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ConditionKey {
+    Type(TypeId),
+    /// A `Condition` declared by name, e.g. in a `GoapDomain`, without a `Condition` type.
+    #[cfg(feature = "assets")]
+    Name(&'static str),
 }
 
-impl PartialEq for GoapState {
+impl PartialEq for ConditionId {
     fn eq(&self, other: &Self) -> bool {
-        self.state == other.state
+        self.key == other.key
     }
 }
 
-impl Hash for GoapState {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        for (key, value) in self.state.iter() {
-            key.hash(state);
-            value.hash(state);
+impl Eq for ConditionId {}
+
+impl Hash for ConditionId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+impl ConditionId {
+    pub(crate) fn of<T: Condition + 'static>() -> Self {
+        Self {
+            key: ConditionKey::Type(TypeId::of::<T>()),
+            name: T::name(),
         }
     }
+
+    pub(crate) fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Finds a registered `Condition` by its name.
+    #[cfg(feature = "serde")]
+    pub(crate) fn find(name: &str) -> Option<Self> {
+        condition_registry().read().unwrap().get(name).copied()
+    }
+
+    /// The `Condition` declared by name, e.g. in a `GoapDomain`.
+    ///
+    /// If a `Condition` type is registered with this name, that `Condition` is returned.
+    #[cfg(feature = "assets")]
+    pub(crate) fn named(name: &str) -> Self {
+        if let Some(condition) = Self::find(name) {
+            return condition;
+        }
+
+        let name = crate::common::intern(name.to_string());
+        *condition_registry()
+            .write()
+            .unwrap()
+            .entry(name)
+            .or_insert(Self {
+                key: ConditionKey::Name(name),
+                name,
+            })
+    }
+
+    /// Registers this `Condition` by its name, failing if another `Condition` has the same name.
+    #[cfg(feature = "serde")]
+    fn register(self) -> Result<(), Self> {
+        if Self::find(self.name) == Some(self) {
+            return Ok(());
+        }
+
+        let registered = *condition_registry()
+            .write()
+            .unwrap()
+            .entry(self.name)
+            .or_insert(self);
+
+        if registered == self {
+            Ok(())
+        } else {
+            Err(registered)
+        }
+    }
+}
+
+/// Every `Condition` that has been registered or serialized, by name.
+#[cfg(feature = "serde")]
+fn condition_registry() -> &'static RwLock<HashMap<&'static str, ConditionId>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<&'static str, ConditionId>>> = OnceLock::new();
+
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// The error returned when registering a `Condition` whose name is already the name of another
+/// `Condition`.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConditionNameConflict {
+    /// The type name of the `Condition` that could not be registered.
+    pub condition: &'static str,
+    /// The name shared by both `Condition`s.
+    pub name: &'static str,
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for ConditionNameConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Condition {} is named {:?}, which is already the name of another Condition",
+            self.condition, self.name
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for ConditionNameConflict {}
+
+/// Registers a `Condition` by its name, so that it can be deserialized. `Condition`s are also
+/// registered when they are first serialized.
+#[cfg(feature = "serde")]
+pub(crate) fn register_condition<T: Condition + 'static>() -> Result<(), ConditionNameConflict> {
+    ConditionId::of::<T>()
+        .register()
+        .map_err(|_| ConditionNameConflict {
+            condition: std::any::type_name::<T>(),
+            name: T::name(),
+        })
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for ConditionId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.register().is_err() {
+            return Err(serde::ser::Error::custom(format!(
+                "more than one Condition is named {:?}",
+                self.name
+            )));
+        }
+
+        serializer.serialize_str(self.name)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ConditionId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        ConditionId::find(&name).ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "unknown Condition {name:?}, which must be registered before deserializing"
+            ))
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct GoapState {
+    pub(crate) state: State<ConditionId>,
 }
 
 impl GoapState {
     pub(crate) fn new() -> Self {
         Self {
-            state: HashMap::new(),
+            state: State::new(),
         }
     }
 
-    pub(crate) fn insert<T: 'static>(&mut self, value: bool) {
-        self.state.insert(TypeId::of::<T>(), value);
+    pub(crate) fn insert<T: Condition + 'static>(&mut self, value: bool) {
+        self.state.insert(ConditionId::of::<T>(), value);
+    }
+
+    pub(crate) fn get<T: Condition + 'static>(&self) -> Option<bool> {
+        self.state.get(&ConditionId::of::<T>())
+    }
+
+    #[cfg(feature = "assets")]
+    pub(crate) fn insert_id(&mut self, condition: ConditionId, value: bool) {
+        self.state.insert(condition, value);
+    }
+
+    pub(crate) fn remove<T: Condition + 'static>(&mut self) {
+        self.state.remove(&ConditionId::of::<T>());
+    }
+
+    pub(crate) fn entries(&self) -> impl Iterator<Item = (ConditionId, bool)> + '_ {
+        self.state
+            .iter()
+            .map(|(condition, value)| (*condition, value))
+    }
+
+    /// The name and value of each `Condition` in this state.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&'static str, bool)> + '_ {
+        self.state
+            .iter()
+            .map(|(condition, value)| (condition.name(), value))
     }
 
     pub(crate) fn extend(&mut self, other: GoapState) {
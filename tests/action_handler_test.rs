@@ -0,0 +1,202 @@
+#![cfg(feature = "bevy")]
+
+use bevy::{
+    ecs::system::{lifetimeless::SResMut, SystemParamItem},
+    prelude::{App, Component, Resource, With},
+};
+use bevy_goap::{
+    Action, ActionProgress, Actor, ActorState, Condition, GoapAction, GoapAppExt, GoapPlugin,
+};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+struct HasAxeCondition;
+
+#[derive(Condition)]
+struct HasWoodCondition;
+
+#[derive(Resource, Default, Debug, PartialEq, Eq)]
+struct Calls {
+    evaluated: u32,
+    started: u32,
+    ticked: u32,
+    cancelled: u32,
+    not_in_plan: u32,
+}
+
+#[derive(Component, Clone)]
+struct GetAxeAction;
+
+impl GoapAction for GetAxeAction {
+    type Param = SResMut<Calls>;
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _calls: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Complete
+    }
+}
+
+#[derive(Component, Clone)]
+struct ChopTreeAction {
+    ticks_to_complete: u32,
+}
+
+impl GoapAction for ChopTreeAction {
+    type Param = SResMut<Calls>;
+
+    fn evaluate(
+        &mut self,
+        _action: &mut Action,
+        calls: &mut SystemParamItem<Self::Param>,
+    ) -> bevy_goap::EvaluationResult {
+        calls.evaluated += 1;
+        bevy_goap::EvaluationResult::Success
+    }
+
+    fn on_start(
+        &mut self,
+        _action: &Action,
+        calls: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        calls.started += 1;
+        ActionProgress::Running
+    }
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        calls: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        calls.ticked += 1;
+
+        if calls.ticked >= self.ticks_to_complete {
+            ActionProgress::Complete
+        } else {
+            ActionProgress::Running
+        }
+    }
+
+    fn on_cancel(&mut self, _action: &Action, calls: &mut SystemParamItem<Self::Param>) {
+        calls.cancelled += 1;
+    }
+}
+
+#[derive(Component, Clone)]
+struct CollectWoodAction;
+
+impl GoapAction for CollectWoodAction {
+    type Param = SResMut<Calls>;
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _calls: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Complete
+    }
+
+    fn on_not_in_plan(
+        &mut self,
+        _action: &Action,
+        evaluated: bool,
+        calls: &mut SystemParamItem<Self::Param>,
+    ) {
+        assert!(evaluated);
+        calls.not_in_plan += 1;
+    }
+}
+
+fn create_app(ticks_to_complete: u32) -> App {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default())
+        .init_resource::<Calls>()
+        .add_goap_action::<GetAxeAction>()
+        .add_goap_action::<ChopTreeAction>()
+        .add_goap_action::<CollectWoodAction>();
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HasAxeCondition, false)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_goal(HasWoodCondition, true)
+        .with_action(
+            Action::build(GetAxeAction)
+                .with_precondition(HasAxeCondition, false)
+                .with_postcondition(HasAxeCondition, true),
+        )
+        .with_action(
+            Action::build(ChopTreeAction { ticks_to_complete })
+                .with_precondition(HasAxeCondition, true)
+                .with_postcondition(HasWoodCondition, true),
+        )
+        // Collecting wood requires an axe, but costs more than getting an axe and chopping a tree.
+        .with_action(
+            Action::build(CollectWoodAction)
+                .with_precondition(HasAxeCondition, true)
+                .with_precondition(HasWoodCondition, false)
+                .with_postcondition(HasWoodCondition, true)
+                .with_postcondition(HasAxeCondition, true),
+        );
+
+    app.world.spawn(lumberjack);
+    app
+}
+
+fn actor_state(app: &mut App) -> ActorState {
+    app.world
+        .query::<(&ActorState, With<Lumberjack>)>()
+        .single(&app.world)
+        .0
+        .clone()
+}
+
+#[test]
+fn goap_actions_complete_plan() {
+    let mut app = create_app(2);
+
+    for _ in 0..8 {
+        app.update();
+    }
+
+    assert_eq!(actor_state(&mut app), ActorState::CompletedPlan);
+    assert_eq!(
+        *app.world.resource::<Calls>(),
+        Calls {
+            evaluated: 1,
+            started: 1,
+            ticked: 2,
+            cancelled: 0,
+            not_in_plan: 1,
+        }
+    );
+}
+
+#[test]
+fn goap_action_is_cancelled_when_actor_replans() {
+    let mut app = create_app(u32::MAX);
+
+    for _ in 0..6 {
+        app.update();
+    }
+
+    assert_eq!(actor_state(&mut app), ActorState::ExecutingPlan);
+    assert_eq!(app.world.resource::<Calls>().cancelled, 0);
+
+    *app.world
+        .query_filtered::<&mut ActorState, With<Lumberjack>>()
+        .single_mut(&mut app.world) = ActorState::RequiresPlan;
+
+    // Let the plan request set the actions to be evaluated.
+    app.update();
+    assert_eq!(app.world.resource::<Calls>().cancelled, 0);
+
+    // Let the running action observe that it is being evaluated again.
+    app.update();
+
+    assert_eq!(app.world.resource::<Calls>().cancelled, 1);
+    assert_eq!(app.world.resource::<Calls>().evaluated, 2);
+}
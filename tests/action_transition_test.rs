@@ -0,0 +1,103 @@
+#![cfg(feature = "bevy")]
+
+use bevy::prelude::{App, Component, EventReader, Query, ResMut, Resource, With};
+use bevy_goap::{
+    Action, ActionState, Actor, ActorState, Condition, EvaluationResult, GoapPlugin, GoapStage,
+    InvalidActionTransition,
+};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+struct HasWoodCondition;
+
+#[derive(Component, Clone)]
+struct ChopTreeAction;
+
+#[derive(Component, Clone)]
+struct CollectWoodAction;
+
+#[derive(Resource, Default)]
+struct InvalidTransitions(Vec<(ActionState, ActionState)>);
+
+fn chop_tree_action_system(mut query: Query<&mut ActionState, With<ChopTreeAction>>) {
+    for mut action_state in query.iter_mut() {
+        match *action_state {
+            ActionState::Evaluate => {
+                *action_state = ActionState::EvaluationComplete(EvaluationResult::Success);
+            }
+            ActionState::NotInPlan(_) => *action_state = ActionState::Idle,
+            ActionState::Started => *action_state = ActionState::Executing,
+            ActionState::Executing => *action_state = ActionState::Complete,
+            _ => {}
+        }
+    }
+}
+
+/// An action system that tries to complete its action without it ever being in a plan.
+fn collect_wood_action_system(mut query: Query<&mut ActionState, With<CollectWoodAction>>) {
+    for mut action_state in query.iter_mut() {
+        match *action_state {
+            ActionState::Evaluate => {
+                *action_state = ActionState::EvaluationComplete(EvaluationResult::Failure);
+            }
+            _ => *action_state = ActionState::Complete,
+        }
+    }
+}
+
+fn record_invalid_transitions_system(
+    mut ev_invalid_transition: EventReader<InvalidActionTransition>,
+    mut invalid_transitions: ResMut<InvalidTransitions>,
+) {
+    for ev in ev_invalid_transition.iter() {
+        invalid_transitions.0.push((ev.from, ev.to));
+    }
+}
+
+#[test]
+fn invalid_action_transitions_are_reported_and_reverted() {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default())
+        .init_resource::<InvalidTransitions>()
+        .add_system_to_stage(GoapStage::Actions, chop_tree_action_system)
+        .add_system_to_stage(GoapStage::Actions, collect_wood_action_system)
+        .add_system_to_stage(GoapStage::Actors, record_invalid_transitions_system);
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_goal(HasWoodCondition, true)
+        .with_action(Action::build(ChopTreeAction).with_postcondition(HasWoodCondition, true))
+        .with_action(Action::build(CollectWoodAction).with_postcondition(HasWoodCondition, true));
+
+    app.world.spawn(lumberjack);
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    let invalid_transitions = &app.world.resource::<InvalidTransitions>().0;
+
+    assert!(!invalid_transitions.is_empty());
+    assert!(invalid_transitions
+        .iter()
+        .all(|(_, to)| *to == ActionState::Complete));
+
+    // The invalid completions must not have advanced the plan, which is only completed by chopping
+    // a tree.
+    assert_eq!(
+        app.world
+            .query::<(&ActorState, With<Lumberjack>)>()
+            .single(&app.world)
+            .0,
+        &ActorState::CompletedPlan
+    );
+
+    assert_ne!(
+        app.world
+            .query_filtered::<&ActionState, With<CollectWoodAction>>()
+            .single(&app.world),
+        &ActionState::Complete
+    );
+}
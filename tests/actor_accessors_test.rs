@@ -0,0 +1,213 @@
+#![cfg(feature = "bevy")]
+
+mod common;
+
+use bevy::{
+    ecs::system::SystemParamItem,
+    prelude::{App, Component, Entity, With},
+};
+use bevy_goap::{
+    Action, ActionProgress, Actor, ActorState, Condition, GoapAction, GoapAppExt, GoapPlugin,
+    PlannerSettings,
+};
+use common::{actor_state, planned_labels, update_until_executing, Timed};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+#[condition(name = "HasAxe")]
+struct HasAxeCondition;
+
+#[derive(Condition)]
+#[condition(name = "HasWood")]
+struct HasWoodCondition;
+
+#[derive(Component, Clone)]
+struct GetAxeAction;
+
+impl GoapAction for GetAxeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Complete
+    }
+}
+
+#[derive(Component, Clone)]
+struct ChopTreeAction;
+
+impl GoapAction for ChopTreeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Running
+    }
+}
+
+fn create_app() -> App {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default())
+        .add_goap_action::<GetAxeAction>()
+        .add_goap_action::<ChopTreeAction>();
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HasAxeCondition, false)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_goal(HasWoodCondition, true)
+        .with_action(
+            Action::build(GetAxeAction)
+                .with_precondition(HasAxeCondition, false)
+                .with_postcondition(HasAxeCondition, true),
+        )
+        .with_action(
+            Action::build(ChopTreeAction)
+                .with_precondition(HasAxeCondition, true)
+                .with_postcondition(HasWoodCondition, true),
+        );
+
+    app.world.spawn(lumberjack);
+    app
+}
+
+fn action_entity<T: Component>(app: &mut App) -> Entity {
+    app.world
+        .query_filtered::<Entity, With<T>>()
+        .single(&app.world)
+}
+
+fn sorted(state: impl Iterator<Item = (&'static str, bool)>) -> Vec<(&'static str, bool)> {
+    let mut state = state.collect::<Vec<_>>();
+    state.sort();
+    state
+}
+
+#[test]
+fn actor_exposes_states_before_planning() {
+    let mut app = create_app();
+    app.update();
+
+    let actor = app.world.query::<&Actor>().single(&app.world);
+
+    assert_eq!(actor.actions().len(), 2);
+    assert!(actor.planned_actions().is_empty());
+    assert_eq!(actor.current_step(), None);
+    assert_eq!(actor.plan_cost(), None);
+    assert_eq!(actor.expected_end_state().count(), 0);
+    assert_eq!(
+        sorted(actor.current_state()),
+        vec![("HasAxe", false), ("HasWood", false)]
+    );
+    assert_eq!(sorted(actor.goal()), vec![("HasWood", true)]);
+}
+
+#[test]
+fn actor_exposes_plan_progress() {
+    let mut app = create_app();
+    app.update();
+
+    while *app.world.query::<&ActorState>().single(&app.world) != ActorState::ExecutingPlan {
+        app.update();
+    }
+
+    let get_axe = action_entity::<GetAxeAction>(&mut app);
+    let chop_tree = action_entity::<ChopTreeAction>(&mut app);
+
+    let actor = app.world.query::<&Actor>().single(&app.world);
+
+    assert_eq!(actor.planned_actions(), &[get_axe, chop_tree]);
+    assert_eq!(actor.current_step(), Some(0));
+    assert_eq!(actor.current_action(), Some(get_axe));
+    assert_eq!(actor.plan_cost(), Some(2));
+    assert_eq!(
+        sorted(actor.expected_end_state()),
+        vec![("HasAxe", true), ("HasWood", true)]
+    );
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    let actor = app.world.query::<&Actor>().single(&app.world);
+
+    assert_eq!(actor.current_step(), Some(1));
+    assert_eq!(actor.current_action(), Some(chop_tree));
+    assert_eq!(
+        sorted(actor.current_state()),
+        vec![("HasAxe", true), ("HasWood", false)]
+    );
+}
+
+#[derive(Component, Clone)]
+struct Worker;
+
+#[derive(Condition)]
+#[condition(name = "Hungry")]
+struct HungryCondition;
+
+#[derive(Condition)]
+#[condition(name = "Worked")]
+struct WorkedCondition;
+
+#[derive(Condition)]
+#[condition(name = "Rested")]
+struct RestedCondition;
+
+#[test]
+fn current_step_tells_repeated_actions_apart() {
+    let mut app = common::create_app(PlannerSettings::default());
+
+    let worker = Actor::build(Worker)
+        .with_initial_condition(HungryCondition, true)
+        .with_initial_condition(WorkedCondition, false)
+        .with_initial_condition(RestedCondition, false)
+        .with_goal(WorkedCondition, true)
+        .with_goal(RestedCondition, true)
+        .with_action(
+            Action::build(Timed::new("Work", 1))
+                .with_precondition(HungryCondition, false)
+                .with_postcondition(HungryCondition, true)
+                .with_postcondition(WorkedCondition, true),
+        )
+        .with_action(
+            Action::build(Timed::new("Rest", 1))
+                .with_precondition(HungryCondition, true)
+                .with_precondition(WorkedCondition, true)
+                .with_postcondition(WorkedCondition, false)
+                .with_postcondition(RestedCondition, true),
+        )
+        .with_action(
+            Action::build(Timed::new("Eat", 1))
+                .with_precondition(HungryCondition, true)
+                .with_postcondition(HungryCondition, false),
+        );
+    let actor_entity = app.world.spawn(worker).id();
+
+    update_until_executing(&mut app, actor_entity);
+    assert_eq!(
+        planned_labels(&app, actor_entity),
+        ["Eat", "Work", "Rest", "Eat", "Work"]
+    );
+
+    let mut steps = vec![];
+    for _ in 0..20 {
+        if actor_state(&app, actor_entity) != ActorState::ExecutingPlan {
+            break;
+        }
+
+        steps.push(app.world.get::<Actor>(actor_entity).unwrap().current_step());
+        app.update();
+    }
+    steps.dedup();
+
+    assert_eq!(steps, [Some(0), Some(1), Some(2), Some(3), Some(4)]);
+    assert_eq!(actor_state(&app, actor_entity), ActorState::CompletedPlan);
+}
@@ -0,0 +1,137 @@
+#![cfg(feature = "bevy")]
+
+use bevy::{
+    ecs::system::{
+        lifetimeless::{SQuery, Write},
+        SystemParamItem,
+    },
+    prelude::{App, Component},
+};
+use bevy_goap::{
+    Action, ActionProgress, Actor, ActorState, Blackboard, BlackboardKey, Condition, GoapAction,
+    GoapAppExt, GoapPlugin,
+};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+struct FoundTreeCondition;
+
+#[derive(Condition)]
+struct ChoppedTreeCondition;
+
+struct TargetTree;
+
+impl BlackboardKey for TargetTree {
+    type Value = u32;
+}
+
+struct Hint;
+
+impl BlackboardKey for Hint {
+    type Value = String;
+}
+
+#[derive(Component, Clone)]
+struct FindTreeAction;
+
+impl GoapAction for FindTreeAction {
+    type Param = SQuery<Write<Blackboard>>;
+
+    fn tick(
+        &mut self,
+        action: &Action,
+        blackboards: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        let mut blackboard = blackboards.get_mut(action.actor_entity).unwrap();
+        blackboard.insert(TargetTree, 7);
+
+        ActionProgress::Complete
+    }
+}
+
+#[derive(Component, Clone, Default)]
+struct ChopTreeAction {
+    chopped: Option<u32>,
+}
+
+impl GoapAction for ChopTreeAction {
+    type Param = SQuery<Write<Blackboard>>;
+
+    fn tick(
+        &mut self,
+        action: &Action,
+        blackboards: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        let mut blackboard = blackboards.get_mut(action.actor_entity).unwrap();
+
+        match blackboard.remove(TargetTree) {
+            Some(tree) => {
+                self.chopped = Some(tree);
+                ActionProgress::Complete
+            }
+            None => ActionProgress::Failure,
+        }
+    }
+}
+
+#[test]
+fn blackboard_stores_values_by_key() {
+    let mut blackboard = Blackboard::default();
+    assert!(blackboard.is_empty());
+
+    assert_eq!(blackboard.insert(TargetTree, 1), None);
+    assert_eq!(blackboard.insert(TargetTree, 2), Some(1));
+    blackboard.insert(Hint, "closest".to_string());
+
+    assert_eq!(blackboard.get(TargetTree), Some(&2));
+    *blackboard.get_mut(Hint).unwrap() += " first";
+    assert_eq!(blackboard.get(Hint).unwrap(), "closest first");
+
+    assert_eq!(blackboard.remove(TargetTree), Some(2));
+    assert!(!blackboard.contains(TargetTree));
+    assert!(blackboard.contains(Hint));
+    assert_eq!(blackboard.keys().count(), 1);
+
+    blackboard.clear();
+    assert!(blackboard.is_empty());
+}
+
+#[test]
+fn actions_pass_values_through_the_blackboard() {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default())
+        .add_goap_action::<FindTreeAction>()
+        .add_goap_action::<ChopTreeAction>();
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(FoundTreeCondition, false)
+        .with_initial_condition(ChoppedTreeCondition, false)
+        .with_goal(ChoppedTreeCondition, true)
+        .with_action(Action::build(FindTreeAction).with_postcondition(FoundTreeCondition, true))
+        .with_action(
+            Action::build(ChopTreeAction::default())
+                .with_precondition(FoundTreeCondition, true)
+                .with_postcondition(ChoppedTreeCondition, true),
+        );
+
+    let actor_entity = app.world.spawn(lumberjack).id();
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    assert_eq!(
+        *app.world.get::<ActorState>(actor_entity).unwrap(),
+        ActorState::CompletedPlan
+    );
+
+    let chop_tree = app.world.query::<&ChopTreeAction>().single(&app.world);
+    assert_eq!(chop_tree.chopped, Some(7));
+    assert!(app
+        .world
+        .get::<Blackboard>(actor_entity)
+        .unwrap()
+        .is_empty());
+}
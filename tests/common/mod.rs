@@ -0,0 +1,112 @@
+//! A fixture shared by tests whose `Action`s take a number of updates to complete.
+
+// Each test crate only uses some of these helpers.
+#![allow(dead_code)]
+#![cfg(feature = "bevy")]
+
+use bevy::{
+    ecs::system::SystemParamItem,
+    prelude::{App, Component, Entity},
+};
+use bevy_goap::{
+    Action, ActionProgress, Actor, ActorState, GoapAction, GoapAppExt, GoapPlugin, PlannerSettings,
+};
+
+/// Completes, or fails, after `ticks` updates of executing. Tests tell `Timed` `Action`s apart by
+/// their `label`.
+#[derive(Component, Clone)]
+pub struct Timed {
+    pub label: &'static str,
+    pub ticks: u32,
+    pub fails: bool,
+}
+
+impl Timed {
+    pub fn new(label: &'static str, ticks: u32) -> Self {
+        Self {
+            label,
+            ticks,
+            fails: false,
+        }
+    }
+
+    pub fn failing(label: &'static str, ticks: u32) -> Self {
+        Self {
+            label,
+            ticks,
+            fails: true,
+        }
+    }
+}
+
+impl GoapAction for Timed {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        self.ticks = self.ticks.saturating_sub(1);
+
+        if self.ticks > 0 {
+            ActionProgress::Running
+        } else if self.fails {
+            ActionProgress::Failure
+        } else {
+            ActionProgress::Complete
+        }
+    }
+}
+
+/// Creates an app with the `GoapPlugin` and the system driving `Timed` `Action`s.
+pub fn create_app(planner: PlannerSettings) -> App {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin {
+        planner,
+        ..Default::default()
+    })
+    .add_goap_action::<Timed>();
+    app
+}
+
+pub fn update_until_executing(app: &mut App, actor_entity: Entity) {
+    for _ in 0..20 {
+        app.update();
+
+        if actor_state(app, actor_entity) == ActorState::ExecutingPlan {
+            return;
+        }
+    }
+
+    panic!(
+        "the Actor is still {:?} after 20 updates",
+        actor_state(app, actor_entity)
+    );
+}
+
+pub fn actor_state(app: &App, actor_entity: Entity) -> ActorState {
+    app.world.get::<ActorState>(actor_entity).unwrap().clone()
+}
+
+/// The entity of the `Timed` `Action` labelled `label`.
+pub fn timed_entity(app: &mut App, label: &str) -> Entity {
+    app.world
+        .query::<(Entity, &Timed)>()
+        .iter(&app.world)
+        .find(|(_, timed)| timed.label == label)
+        .unwrap()
+        .0
+}
+
+/// The labels of every step of the current plan of the `Actor` on `actor_entity`, including
+/// completed steps.
+pub fn planned_labels(app: &App, actor_entity: Entity) -> Vec<&'static str> {
+    app.world
+        .get::<Actor>(actor_entity)
+        .unwrap()
+        .planned_actions()
+        .iter()
+        .map(|action_entity| app.world.get::<Timed>(*action_entity).unwrap().label)
+        .collect()
+}
@@ -0,0 +1,311 @@
+#![cfg(feature = "bevy")]
+
+use bevy::{
+    ecs::system::{lifetimeless::SRes, SystemParamItem},
+    prelude::{App, Component, Entity, Events, Resource, With},
+};
+use bevy_goap::{
+    Action, ActionFailed, ActionProgress, ActionState, Actor, ActorState, CompoundAction,
+    CompoundActionBuilder, Condition, GoapAction, GoapAppExt, GoapPlugin, SequenceBuilder,
+    SubGoalBuilder,
+};
+
+#[derive(Component, Clone)]
+struct Builder;
+
+#[derive(Condition)]
+#[condition(name = "HasAxe")]
+struct HasAxeCondition;
+
+#[derive(Condition)]
+#[condition(name = "HasWood")]
+struct HasWoodCondition;
+
+#[derive(Condition)]
+#[condition(name = "HouseBuilt")]
+struct HouseBuiltCondition;
+
+#[derive(Resource)]
+struct WallsCollapse(bool);
+
+#[derive(Component, Clone)]
+struct BuildHouseAction;
+
+#[derive(Component, Clone)]
+struct GetAxeAction;
+
+impl GoapAction for GetAxeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Complete
+    }
+}
+
+#[derive(Component, Clone)]
+struct ChopTreeAction;
+
+impl GoapAction for ChopTreeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Complete
+    }
+}
+
+#[derive(Component, Clone)]
+struct RaiseWallsAction;
+
+impl GoapAction for RaiseWallsAction {
+    type Param = SRes<WallsCollapse>;
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        walls_collapse: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        if walls_collapse.0 {
+            ActionProgress::Failure
+        } else {
+            ActionProgress::Complete
+        }
+    }
+}
+
+fn create_app<D: 'static>(
+    build_house: fn() -> CompoundActionBuilder<D>,
+    walls_collapse: bool,
+) -> App {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default())
+        .insert_resource(WallsCollapse(walls_collapse))
+        .add_goap_action::<GetAxeAction>()
+        .add_goap_action::<ChopTreeAction>()
+        .add_goap_action::<RaiseWallsAction>();
+
+    let builder = Actor::build(Builder)
+        .with_initial_condition(HasAxeCondition, false)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_initial_condition(HouseBuiltCondition, false)
+        .with_goal(HouseBuiltCondition, true)
+        .with_action(build_house());
+
+    app.world.spawn(builder);
+    app
+}
+
+fn sequence() -> SequenceBuilder {
+    CompoundAction::sequence(BuildHouseAction)
+        .with_postcondition(HouseBuiltCondition, true)
+        .with_sub_action(Action::build(GetAxeAction).with_postcondition(HasAxeCondition, true))
+        .with_sub_action(
+            Action::build(ChopTreeAction)
+                .with_precondition(HasAxeCondition, true)
+                .with_postcondition(HasWoodCondition, true),
+        )
+        .with_sub_action(
+            Action::build(RaiseWallsAction)
+                .with_precondition(HasWoodCondition, true)
+                .with_postcondition(HouseBuiltCondition, true),
+        )
+}
+
+fn sub_goal() -> SubGoalBuilder {
+    CompoundAction::sub_goal(BuildHouseAction)
+        .with_postcondition(HouseBuiltCondition, true)
+        .with_sub_goal(HouseBuiltCondition, true)
+        // Added in reverse, so that the sub-plan must order them.
+        .with_sub_action(
+            Action::build(RaiseWallsAction)
+                .with_precondition(HasWoodCondition, true)
+                .with_postcondition(HouseBuiltCondition, true),
+        )
+        .with_sub_action(
+            Action::build(ChopTreeAction)
+                .with_precondition(HasAxeCondition, true)
+                .with_postcondition(HasWoodCondition, true),
+        )
+        .with_sub_action(Action::build(GetAxeAction).with_postcondition(HasAxeCondition, true))
+}
+
+fn entity<T: Component>(app: &mut App) -> Entity {
+    app.world
+        .query_filtered::<Entity, With<T>>()
+        .single(&app.world)
+}
+
+fn actor_state(app: &mut App) -> ActorState {
+    app.world.query::<&ActorState>().single(&app.world).clone()
+}
+
+fn update_until(app: &mut App, state: ActorState) {
+    for _ in 0..20 {
+        app.update();
+
+        if actor_state(app) == state {
+            return;
+        }
+    }
+
+    panic!("ActorState {state:?} was not reached");
+}
+
+#[test]
+fn sequence_executes_sub_actions_in_order() {
+    let mut app = create_app(sequence, false);
+    update_until(&mut app, ActorState::ExecutingPlan);
+
+    let build_house = entity::<BuildHouseAction>(&mut app);
+    let get_axe = entity::<GetAxeAction>(&mut app);
+    let chop_tree = entity::<ChopTreeAction>(&mut app);
+    let raise_walls = entity::<RaiseWallsAction>(&mut app);
+
+    let actor = app.world.query::<&Actor>().single(&app.world);
+    assert_eq!(actor.planned_actions(), &[build_house]);
+    assert_eq!(actor.actions(), &[build_house]);
+
+    app.update();
+
+    let actor = app.world.query::<&Actor>().single(&app.world);
+    let sub_plan = &actor.sub_plans()[0];
+
+    assert_eq!(sub_plan.compound_action(), build_house);
+    assert_eq!(sub_plan.steps(), &[get_axe, chop_tree, raise_walls]);
+    assert_eq!(sub_plan.current_step(), Some(0));
+    assert_eq!(sub_plan.cost(), 3);
+    assert_eq!(actor.current_step(), Some(0));
+    assert_eq!(actor.current_action(), Some(get_axe));
+    assert_eq!(
+        *app.world.get::<ActionState>(build_house).unwrap(),
+        ActionState::Executing
+    );
+
+    // The sub-action is started, then ticked to completion.
+    app.update();
+    app.update();
+
+    let actor = app.world.query::<&Actor>().single(&app.world);
+    assert_eq!(actor.sub_plans()[0].current_step(), Some(1));
+    assert_eq!(actor.current_action(), Some(chop_tree));
+
+    update_until(&mut app, ActorState::CompletedPlan);
+
+    let actor = app.world.query::<&Actor>().single(&app.world);
+    let mut state = actor.current_state().collect::<Vec<_>>();
+    state.sort();
+
+    assert!(actor.sub_plans().is_empty());
+    assert_eq!(
+        state,
+        vec![("HasAxe", true), ("HasWood", true), ("HouseBuilt", true)]
+    );
+}
+
+#[test]
+fn sub_goal_is_planned_when_started() {
+    let mut app = create_app(sub_goal, false);
+    update_until(&mut app, ActorState::ExecutingPlan);
+    app.update();
+
+    let get_axe = entity::<GetAxeAction>(&mut app);
+    let chop_tree = entity::<ChopTreeAction>(&mut app);
+    let raise_walls = entity::<RaiseWallsAction>(&mut app);
+
+    let actor = app.world.query::<&Actor>().single(&app.world);
+    assert_eq!(
+        actor.sub_plans()[0].steps(),
+        &[get_axe, chop_tree, raise_walls]
+    );
+
+    update_until(&mut app, ActorState::CompletedPlan);
+}
+
+#[test]
+fn sub_action_failure_fails_compound_action_and_plan() {
+    let mut app = create_app(sequence, true);
+    update_until(&mut app, ActorState::FailedDuringPlan);
+
+    let build_house = entity::<BuildHouseAction>(&mut app);
+    let raise_walls = entity::<RaiseWallsAction>(&mut app);
+
+    let failed = app
+        .world
+        .resource::<Events<ActionFailed>>()
+        .iter_current_update_events()
+        .map(|event| event.action_entity)
+        .collect::<Vec<_>>();
+
+    assert_eq!(failed, vec![raise_walls, build_house]);
+    assert!(app
+        .world
+        .query::<&Actor>()
+        .single(&app.world)
+        .sub_plans()
+        .is_empty());
+    assert_eq!(
+        *app.world.get::<ActionState>(build_house).unwrap(),
+        ActionState::Idle
+    );
+}
+
+#[test]
+fn unreachable_sub_goal_fails_plan() {
+    fn unreachable() -> SubGoalBuilder {
+        CompoundAction::sub_goal(BuildHouseAction)
+            .with_postcondition(HouseBuiltCondition, true)
+            .with_sub_goal(HouseBuiltCondition, true)
+            .with_sub_action(Action::build(GetAxeAction).with_postcondition(HasAxeCondition, true))
+    }
+
+    let mut app = create_app(unreachable, false);
+    update_until(&mut app, ActorState::FailedDuringPlan);
+
+    assert!(app
+        .world
+        .query::<&Actor>()
+        .single(&app.world)
+        .sub_plans()
+        .is_empty());
+}
+
+#[test]
+fn unmet_sequence_precondition_fails_plan() {
+    fn without_axe() -> SequenceBuilder {
+        CompoundAction::sequence(BuildHouseAction)
+            .with_postcondition(HouseBuiltCondition, true)
+            .with_sub_action(
+                Action::build(ChopTreeAction)
+                    .with_precondition(HasAxeCondition, true)
+                    .with_postcondition(HasWoodCondition, true),
+            )
+            .with_sub_action(
+                Action::build(RaiseWallsAction)
+                    .with_precondition(HasWoodCondition, true)
+                    .with_postcondition(HouseBuiltCondition, true),
+            )
+    }
+
+    let mut app = create_app(without_axe, false);
+    update_until(&mut app, ActorState::FailedDuringPlan);
+
+    // No sub-action was started.
+    assert!(app
+        .world
+        .query::<&Actor>()
+        .single(&app.world)
+        .sub_plans()
+        .is_empty());
+    let chop_tree = entity::<ChopTreeAction>(&mut app);
+    assert_eq!(
+        *app.world.get::<ActionState>(chop_tree).unwrap(),
+        ActionState::Idle
+    );
+}
@@ -0,0 +1,159 @@
+#![cfg(feature = "bevy")]
+
+mod common;
+
+use bevy::prelude::{App, Component, Entity, Events};
+use bevy_goap::{
+    Action, ActionFailed, ActionState, Actor, ActorState, Condition, PlanFailed, PlannerSettings,
+};
+use common::{actor_state, timed_entity, update_until_executing, Timed};
+
+#[derive(Component, Clone)]
+struct Soldier;
+
+#[derive(Condition)]
+#[condition(name = "InCover")]
+struct InCoverCondition;
+
+#[derive(Condition)]
+#[condition(name = "Loaded")]
+struct LoadedCondition;
+
+#[derive(Condition)]
+#[condition(name = "TargetDown")]
+struct TargetDownCondition;
+
+fn create_app(reload_channel: &'static str) -> (App, Entity) {
+    let mut app = common::create_app(PlannerSettings::default());
+
+    let soldier = Actor::build(Soldier)
+        .with_initial_condition(InCoverCondition, false)
+        .with_initial_condition(LoadedCondition, false)
+        .with_initial_condition(TargetDownCondition, false)
+        .with_goal(TargetDownCondition, true)
+        .with_action(
+            Action::build(Timed::new("MoveToCover", 3))
+                .with_channel("legs")
+                .with_postcondition(InCoverCondition, true),
+        )
+        .with_action(
+            Action::build(Timed::new("Reload", 1))
+                .with_channel(reload_channel)
+                .with_postcondition(LoadedCondition, true),
+        )
+        .with_action(
+            Action::build(Timed::new("Shoot", 1))
+                .with_channel("hands")
+                .with_precondition(InCoverCondition, true)
+                .with_precondition(LoadedCondition, true)
+                .with_postcondition(TargetDownCondition, true),
+        );
+
+    let actor_entity = app.world.spawn(soldier).id();
+    (app, actor_entity)
+}
+
+fn action_state(app: &App, action_entity: Entity) -> ActionState {
+    *app.world.get::<ActionState>(action_entity).unwrap()
+}
+
+fn executing_actions(app: &App, actor_entity: Entity) -> Vec<Entity> {
+    app.world
+        .get::<Actor>(actor_entity)
+        .unwrap()
+        .executing_actions()
+}
+
+#[test]
+fn actions_on_different_channels_execute_concurrently() {
+    let (mut app, actor_entity) = create_app("hands");
+    update_until_executing(&mut app, actor_entity);
+
+    let move_to_cover = timed_entity(&mut app, "MoveToCover");
+    let reload = timed_entity(&mut app, "Reload");
+    let shoot = timed_entity(&mut app, "Shoot");
+
+    let mut executing = executing_actions(&app, actor_entity);
+    executing.sort();
+    let mut expected = vec![move_to_cover, reload];
+    expected.sort();
+
+    assert_eq!(executing, expected);
+    assert_eq!(action_state(&app, move_to_cover), ActionState::Started);
+    assert_eq!(action_state(&app, reload), ActionState::Started);
+    assert_eq!(action_state(&app, shoot), ActionState::WaitingToStart);
+
+    app.update();
+
+    assert_eq!(action_state(&app, move_to_cover), ActionState::Executing);
+    assert_eq!(action_state(&app, reload), ActionState::Executing);
+
+    app.update();
+
+    // Reloading completed while still moving to cover, but shooting requires both.
+    assert_eq!(executing_actions(&app, actor_entity), vec![move_to_cover]);
+    assert_eq!(action_state(&app, shoot), ActionState::WaitingToStart);
+
+    app.update();
+    app.update();
+
+    assert_eq!(executing_actions(&app, actor_entity), vec![shoot]);
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert_eq!(actor_state(&app, actor_entity), ActorState::CompletedPlan);
+}
+
+#[test]
+fn actions_on_the_same_channel_execute_in_turn() {
+    let (mut app, actor_entity) = create_app("legs");
+    update_until_executing(&mut app, actor_entity);
+
+    assert_eq!(executing_actions(&app, actor_entity).len(), 1);
+
+    while actor_state(&app, actor_entity) == ActorState::ExecutingPlan {
+        assert!(executing_actions(&app, actor_entity).len() <= 1);
+        app.update();
+    }
+
+    assert_eq!(actor_state(&app, actor_entity), ActorState::CompletedPlan);
+}
+
+#[test]
+fn concurrent_failures_fail_the_plan_once() {
+    let mut app = common::create_app(PlannerSettings::default());
+
+    let soldier = Actor::build(Soldier)
+        .with_initial_condition(InCoverCondition, false)
+        .with_initial_condition(LoadedCondition, false)
+        .with_goal(InCoverCondition, true)
+        .with_goal(LoadedCondition, true)
+        .with_action(
+            Action::build(Timed::failing("MoveToCover", 1))
+                .with_channel("legs")
+                .with_postcondition(InCoverCondition, true),
+        )
+        .with_action(
+            Action::build(Timed::failing("Reload", 1))
+                .with_channel("hands")
+                .with_postcondition(LoadedCondition, true),
+        );
+    let actor_entity = app.world.spawn(soldier).id();
+
+    update_until_executing(&mut app, actor_entity);
+    assert_eq!(executing_actions(&app, actor_entity).len(), 2);
+
+    app.update();
+    app.update();
+
+    let plan_failed = app.world.resource::<Events<PlanFailed>>();
+    let action_failed = app.world.resource::<Events<ActionFailed>>();
+    assert_eq!(plan_failed.get_reader().iter(plan_failed).count(), 1);
+    assert_eq!(action_failed.get_reader().iter(action_failed).count(), 2);
+    assert_eq!(
+        actor_state(&app, actor_entity),
+        ActorState::FailedDuringPlan
+    );
+}
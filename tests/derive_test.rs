@@ -0,0 +1,114 @@
+#![cfg(feature = "bevy")]
+
+use bevy::prelude::{App, Commands, Component, Query, With};
+use bevy_goap::{
+    goap_action, Action, ActionState, Actor, ActorState, Condition, EvaluationResult, GoapPlugin,
+    GoapStage,
+};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+struct HasAxeCondition;
+
+#[derive(Condition)]
+#[condition(name = "HasWood", value = bool)]
+struct HasWoodCondition;
+
+#[derive(Component, Clone, Default)]
+struct GetAxeAction {
+    times_started: u32,
+}
+
+#[goap_action]
+impl GetAxeAction {
+    fn start(&mut self, _action: &Action) -> ActionState {
+        self.times_started += 1;
+        ActionState::Executing
+    }
+
+    fn execute(&self, _action: &Action) -> ActionState {
+        ActionState::Complete
+    }
+}
+
+#[derive(Component, Clone)]
+struct ChopTreeAction;
+
+#[derive(Component)]
+struct Tree;
+
+#[goap_action]
+impl ChopTreeAction {
+    fn evaluate(&self, action: &mut Action, trees: &Query<&Tree>) -> EvaluationResult {
+        action.update_cost(trees.iter().count() as u32);
+        EvaluationResult::Success
+    }
+
+    fn execute(&self, commands: &mut Commands, trees: &bevy::prelude::Query<&Tree>) -> ActionState {
+        if trees.iter().count() > 0 {
+            commands.spawn(Tree);
+        }
+
+        ActionState::Complete
+    }
+}
+
+#[test]
+fn derived_condition_names() {
+    assert_eq!(
+        HasAxeCondition::name(),
+        std::any::type_name::<HasAxeCondition>()
+    );
+    assert_eq!(HasWoodCondition::name(), "HasWood");
+}
+
+#[test]
+fn goap_action_systems_complete_plan() {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default());
+    app.add_system_to_stage(GoapStage::Actions, GetAxeAction::action_system);
+    app.add_system_to_stage(GoapStage::Actions, ChopTreeAction::action_system);
+
+    app.world.spawn(Tree);
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HasAxeCondition, false)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_goal(HasWoodCondition, true)
+        .with_action(
+            Action::build(GetAxeAction::default())
+                .with_precondition(HasAxeCondition, false)
+                .with_postcondition(HasAxeCondition, true),
+        )
+        .with_action(
+            Action::build(ChopTreeAction)
+                .with_precondition(HasAxeCondition, true)
+                .with_postcondition(HasWoodCondition, true),
+        );
+
+    app.world.spawn(lumberjack);
+
+    for _ in 0..6 {
+        app.update();
+    }
+
+    assert_eq!(
+        app.world
+            .query::<(&ActorState, With<Lumberjack>)>()
+            .single(&app.world)
+            .0,
+        &ActorState::CompletedPlan
+    );
+
+    assert_eq!(
+        app.world
+            .query::<&GetAxeAction>()
+            .single(&app.world)
+            .times_started,
+        1
+    );
+
+    assert_eq!(app.world.query::<&Tree>().iter(&app.world).len(), 2);
+}
@@ -0,0 +1,217 @@
+#![cfg(feature = "assets")]
+
+use bevy::{
+    asset::AssetPlugin,
+    ecs::system::SystemParamItem,
+    prelude::{App, Assets, Component, Entity, Handle, MinimalPlugins, With},
+};
+use bevy_goap::{
+    Action, ActionDefinition, ActionProgress, Actor, ActorState, DomainAction, DomainActor,
+    GoapAction, GoapAppExt, GoapAssetPlugin, GoapDomain, GoapPlugin,
+};
+
+const LUMBERJACK: &str = include_str!("assets/lumberjack.goap.ron");
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Component, Clone)]
+struct GetAxeAction;
+
+impl GoapAction for GetAxeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Complete
+    }
+}
+
+#[derive(Component, Clone)]
+struct ChopTreeAction;
+
+impl GoapAction for ChopTreeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Running
+    }
+}
+
+#[derive(Component, Clone)]
+struct BuyWoodAction;
+
+impl GoapAction for BuyWoodAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Running
+    }
+}
+
+/// Creates an app with a lumberjack spawned from the domain, which has taken the axe and is
+/// chopping the tree.
+fn create_app() -> (App, Handle<GoapDomain>, Entity) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin::default())
+        .add_plugin(GoapPlugin::default())
+        .add_plugin(GoapAssetPlugin)
+        .add_goap_action::<GetAxeAction>()
+        .add_goap_action::<ChopTreeAction>()
+        .add_goap_action::<BuyWoodAction>()
+        .register_goap_marker("Lumberjack", Lumberjack)
+        .register_goap_marker("GetAxe", GetAxeAction)
+        .register_goap_marker("ChopTree", ChopTreeAction)
+        .register_goap_marker("BuyWood", BuyWoodAction);
+
+    let handle = app
+        .world
+        .resource_mut::<Assets<GoapDomain>>()
+        .add(GoapDomain::from_ron(LUMBERJACK).unwrap());
+
+    let actor_entity = app
+        .world
+        .spawn(DomainActor::new(handle.clone(), "lumberjack"))
+        .id();
+
+    for _ in 0..8 {
+        app.update();
+    }
+
+    let actor = app.world.get::<Actor>(actor_entity).unwrap();
+    assert_eq!(actor.current_step(), Some(1));
+    assert_eq!(actor.actions().len(), 2);
+
+    (app, handle, actor_entity)
+}
+
+fn action_entity<T: Component>(app: &mut App) -> Entity {
+    app.world
+        .query_filtered::<Entity, With<T>>()
+        .single(&app.world)
+}
+
+#[test]
+fn modified_domain_updates_actor() {
+    let (mut app, handle, actor_entity) = create_app();
+    let chop_tree = action_entity::<ChopTreeAction>(&mut app);
+
+    {
+        let mut domains = app.world.resource_mut::<Assets<GoapDomain>>();
+        let domain = domains.get_mut(&handle).unwrap();
+
+        domain.actions[1].cost = 5;
+        domain.actions.push(ActionDefinition {
+            name: "buy_wood".to_string(),
+            marker: "BuyWood".to_string(),
+            preconditions: Default::default(),
+            postconditions: [("HasWood".to_string(), true)].into(),
+            cost: 1,
+            channels: vec![],
+        });
+        domain.actors[0].actions = vec!["chop_tree".to_string(), "buy_wood".to_string()];
+    }
+
+    for _ in 0..8 {
+        app.update();
+    }
+
+    let buy_wood = action_entity::<BuyWoodAction>(&mut app);
+
+    assert!(app
+        .world
+        .query_filtered::<Entity, With<GetAxeAction>>()
+        .iter(&app.world)
+        .next()
+        .is_none());
+    assert_eq!(app.world.get::<Action>(chop_tree).unwrap().cost(), 5);
+    assert_eq!(
+        app.world.get::<DomainAction>(buy_wood).unwrap().name,
+        "buy_wood"
+    );
+
+    let actor = app.world.get::<Actor>(actor_entity).unwrap();
+    assert_eq!(actor.actions(), &[chop_tree, buy_wood]);
+    assert_eq!(actor.planned_actions(), &[buy_wood]);
+    assert_eq!(
+        *app.world.get::<ActorState>(actor_entity).unwrap(),
+        ActorState::ExecutingPlan
+    );
+}
+
+#[test]
+fn invalid_domain_leaves_actor_untouched() {
+    let (mut app, handle, actor_entity) = create_app();
+    let actions_before = app
+        .world
+        .get::<Actor>(actor_entity)
+        .unwrap()
+        .actions()
+        .to_vec();
+
+    app.world
+        .resource_mut::<Assets<GoapDomain>>()
+        .get_mut(&handle)
+        .unwrap()
+        .actions[1]
+        .marker = "Unregistered".to_string();
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    let actor = app.world.get::<Actor>(actor_entity).unwrap();
+    assert_eq!(actor.actions(), actions_before.as_slice());
+    assert_eq!(actor.current_step(), Some(1));
+}
+
+#[test]
+fn reloaded_actor_stops_plan_with_removed_actions() {
+    let (mut app, handle, actor_entity) = create_app();
+    let chop_tree = action_entity::<ChopTreeAction>(&mut app);
+
+    {
+        let mut domains = app.world.resource_mut::<Assets<GoapDomain>>();
+        let domain = domains.get_mut(&handle).unwrap();
+
+        domain.actions.push(ActionDefinition {
+            name: "buy_wood".to_string(),
+            marker: "BuyWood".to_string(),
+            preconditions: Default::default(),
+            postconditions: [("HasWood".to_string(), true)].into(),
+            cost: 1,
+            channels: vec![],
+        });
+        domain.actors[0].actions = vec!["buy_wood".to_string()];
+    }
+
+    while app
+        .world
+        .get::<Actor>(actor_entity)
+        .unwrap()
+        .actions()
+        .contains(&chop_tree)
+    {
+        app.update();
+    }
+
+    let actor = app.world.get::<Actor>(actor_entity).unwrap();
+    assert!(actor.planned_actions().is_empty());
+    assert_eq!(actor.current_step(), None);
+    assert_eq!(
+        *app.world.get::<ActorState>(actor_entity).unwrap(),
+        ActorState::AwaitingPlan
+    );
+}
@@ -0,0 +1,213 @@
+#![cfg(feature = "assets")]
+
+use std::time::{Duration, Instant};
+
+use bevy::{
+    asset::AssetPlugin,
+    ecs::system::SystemParamItem,
+    prelude::{App, AssetServer, Assets, Component, Handle, MinimalPlugins},
+};
+use bevy_goap::{
+    Action, ActionProgress, Actor, ActorState, Condition, DomainError, GoapAction, GoapAppExt,
+    GoapAssetPlugin, GoapDomain, GoapMarkerRegistry, GoapPlugin,
+};
+
+const LUMBERJACK: &str = include_str!("assets/lumberjack.goap.ron");
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+#[condition(name = "HasWood")]
+struct HasWoodCondition;
+
+#[derive(Component, Clone)]
+struct GetAxeAction;
+
+impl GoapAction for GetAxeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Complete
+    }
+}
+
+#[derive(Component, Clone)]
+struct ChopTreeAction;
+
+impl GoapAction for ChopTreeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Complete
+    }
+}
+
+fn markers() -> GoapMarkerRegistry {
+    let mut markers = GoapMarkerRegistry::default();
+    markers.register("Lumberjack", Lumberjack);
+    markers.register("GetAxe", GetAxeAction);
+    markers.register("ChopTree", ChopTreeAction);
+    markers
+}
+
+#[test]
+fn actor_completes_plan_from_domain() {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default())
+        .add_goap_action::<GetAxeAction>()
+        .add_goap_action::<ChopTreeAction>()
+        .register_goap_condition::<HasWoodCondition>()
+        .unwrap();
+
+    let domain = GoapDomain::from_ron(LUMBERJACK).unwrap();
+    let lumberjack = domain.actor("lumberjack", &markers()).unwrap();
+
+    assert!(lumberjack.validate().is_valid());
+
+    let actor_entity = app.world.spawn(lumberjack).id();
+
+    app.update();
+    assert_eq!(
+        app.world.get::<Actor>(actor_entity).unwrap().plan_cost(),
+        None
+    );
+
+    for _ in 0..8 {
+        app.update();
+    }
+
+    assert_eq!(
+        *app.world.get::<ActorState>(actor_entity).unwrap(),
+        ActorState::CompletedPlan
+    );
+
+    let mut actor = app.world.get_mut::<Actor>(actor_entity).unwrap();
+    assert_eq!(actor.plan_cost(), Some(3));
+
+    // The registered Condition type refers to the Condition declared by the domain.
+    actor.update_goal(HasWoodCondition, false);
+    assert!(actor.goal().eq([("HasWood", false)]));
+}
+
+#[test]
+fn json_domain_matches_ron_domain() {
+    let json = r#"{
+        "conditions": ["HasAxe", "HasWood"],
+        "actions": [
+            {
+                "name": "get_axe",
+                "marker": "GetAxe",
+                "preconditions": {"HasAxe": false},
+                "postconditions": {"HasAxe": true}
+            },
+            {
+                "name": "chop_tree",
+                "marker": "ChopTree",
+                "preconditions": {"HasAxe": true},
+                "postconditions": {"HasWood": true},
+                "cost": 2
+            }
+        ],
+        "actors": [
+            {
+                "name": "lumberjack",
+                "marker": "Lumberjack",
+                "initial_state": {"HasAxe": false, "HasWood": false},
+                "goal": {"HasWood": true},
+                "actions": ["get_axe", "chop_tree"]
+            }
+        ]
+    }"#;
+
+    assert_eq!(
+        GoapDomain::from_json(json).unwrap(),
+        GoapDomain::from_ron(LUMBERJACK).unwrap()
+    );
+}
+
+#[test]
+fn unresolved_names_are_errors() {
+    let domain = GoapDomain::from_ron(LUMBERJACK).unwrap();
+
+    assert_eq!(
+        domain.actor("woodcutter", &markers()).err(),
+        Some(DomainError::UnknownActor("woodcutter".to_string()))
+    );
+    assert_eq!(
+        domain
+            .actor("lumberjack", &GoapMarkerRegistry::default())
+            .err(),
+        Some(DomainError::UnknownMarker("GetAxe".to_string()))
+    );
+
+    let mut undeclared = domain.clone();
+    undeclared
+        .conditions
+        .retain(|condition| condition != "HasAxe");
+    assert_eq!(
+        undeclared.actor("lumberjack", &markers()).err(),
+        Some(DomainError::UnknownCondition("HasAxe".to_string()))
+    );
+
+    let mut missing_action = domain;
+    missing_action.actions.remove(0);
+    assert_eq!(
+        missing_action.actor("lumberjack", &markers()).err(),
+        Some(DomainError::UnknownAction {
+            actor: "lumberjack".to_string(),
+            action: "get_axe".to_string(),
+        })
+    );
+}
+
+#[test]
+fn domain_is_loaded_as_asset() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin {
+            asset_folder: "tests/assets".to_string(),
+            watch_for_changes: false,
+        })
+        .add_plugin(GoapAssetPlugin)
+        .register_goap_marker("Lumberjack", Lumberjack)
+        .register_goap_marker("GetAxe", GetAxeAction)
+        .register_goap_marker("ChopTree", ChopTreeAction);
+
+    let handle: Handle<GoapDomain> = app
+        .world
+        .resource::<AssetServer>()
+        .load("lumberjack.goap.ron");
+
+    let started = Instant::now();
+    while app
+        .world
+        .resource::<Assets<GoapDomain>>()
+        .get(&handle)
+        .is_none()
+    {
+        assert!(
+            started.elapsed() < Duration::from_secs(10),
+            "the domain was not loaded"
+        );
+        app.update();
+    }
+
+    let domain = app
+        .world
+        .resource::<Assets<GoapDomain>>()
+        .get(&handle)
+        .unwrap();
+    assert_eq!(domain, &GoapDomain::from_ron(LUMBERJACK).unwrap());
+    assert!(domain
+        .actor("lumberjack", app.world.resource::<GoapMarkerRegistry>())
+        .is_ok());
+}
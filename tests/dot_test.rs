@@ -0,0 +1,139 @@
+#![cfg(feature = "bevy")]
+
+use bevy::prelude::{App, Component, Query};
+use bevy_goap::{
+    domain_to_dot, Action, ActionState, Actor, Condition, EvaluationResult, GoapPlugin, GoapStage,
+    PlannerSettings, SearchTree,
+};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+#[condition(name = "HasAxe")]
+struct HasAxeCondition;
+
+#[derive(Condition)]
+#[condition(name = "HasWood")]
+struct HasWoodCondition;
+
+#[derive(Component, Clone)]
+struct GetAxeAction;
+
+#[derive(Component, Clone)]
+struct ChopTreeAction;
+
+fn evaluate_action_system(mut query: Query<&mut ActionState>) {
+    for mut action_state in query.iter_mut() {
+        match *action_state {
+            ActionState::Evaluate => {
+                *action_state = ActionState::EvaluationComplete(EvaluationResult::Success);
+            }
+            ActionState::NotInPlan(_) => *action_state = ActionState::Idle,
+            _ => {}
+        }
+    }
+}
+
+fn create_app(record_search_tree: bool) -> App {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin {
+        planner: PlannerSettings {
+            record_search_tree,
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .add_system_to_stage(GoapStage::Actions, evaluate_action_system);
+
+    app.world.spawn(
+        Actor::build(Lumberjack)
+            .with_initial_condition(HasAxeCondition, false)
+            .with_initial_condition(HasWoodCondition, false)
+            .with_goal(HasWoodCondition, true)
+            .with_action(
+                Action::build(GetAxeAction)
+                    .with_precondition(HasAxeCondition, false)
+                    .with_postcondition(HasAxeCondition, true),
+            )
+            .with_action(
+                Action::build(ChopTreeAction)
+                    .with_precondition(HasAxeCondition, true)
+                    .with_postcondition(HasWoodCondition, true),
+            ),
+    );
+    app
+}
+
+#[test]
+fn domain_is_exported_as_dot() {
+    let dot = Actor::build(Lumberjack)
+        .with_action(
+            Action::build(GetAxeAction)
+                .with_precondition(HasAxeCondition, false)
+                .with_postcondition(HasAxeCondition, true),
+        )
+        .with_action(
+            Action::build(ChopTreeAction)
+                .with_precondition(HasAxeCondition, true)
+                .with_postcondition(HasWoodCondition, true),
+        )
+        .to_dot();
+
+    assert!(dot.starts_with("digraph domain {"));
+    assert!(dot.contains(&format!(
+        "a0 [label=\"{}\\npre: HasAxe=false\\npost: HasAxe=true\"];",
+        std::any::type_name::<GetAxeAction>()
+    )));
+    assert!(dot.contains("a0 -> a1 [label=\"HasAxe=true\"];"));
+    assert_eq!(dot.matches("->").count(), 1);
+}
+
+#[test]
+fn built_domain_matches_builder_export() {
+    let mut app = create_app(false);
+    app.update();
+
+    let actor = app.world.query::<&Actor>().single(&app.world);
+    let dot = domain_to_dot(
+        actor
+            .actions()
+            .iter()
+            .map(|action_entity| app.world.get::<Action>(*action_entity).unwrap()),
+    );
+
+    assert!(dot.contains("a0 -> a1 [label=\"HasAxe=true\"];"));
+}
+
+#[test]
+fn search_tree_is_recorded() {
+    let mut app = create_app(true);
+    app.update();
+    app.update();
+
+    let search_tree = app.world.query::<&SearchTree>().single(&app.world);
+    let path = search_tree
+        .nodes
+        .iter()
+        .filter(|node| node.on_path)
+        .collect::<Vec<_>>();
+
+    assert_eq!(path.len(), 3);
+    assert_eq!(path[0].action, None);
+    assert_eq!(path[0].h, 1);
+    assert_eq!(
+        path[2].action,
+        Some(std::any::type_name::<ChopTreeAction>())
+    );
+    assert_eq!((path[2].g, path[2].h, path[2].f()), (2, 0, 2));
+    assert!(search_tree.to_dot().contains("penwidth=3"));
+}
+
+#[test]
+fn search_tree_is_not_recorded_by_default() {
+    let mut app = create_app(false);
+    app.update();
+    app.update();
+
+    assert_eq!(app.world.query::<&SearchTree>().iter(&app.world).len(), 0);
+}
@@ -0,0 +1,215 @@
+#![cfg(feature = "bevy")]
+
+use bevy::{
+    ecs::system::{SystemParam, SystemParamItem},
+    prelude::{App, Component, CoreStage, Entity, EventReader, ResMut, Resource, With},
+};
+use bevy_goap::{
+    Action, ActionCompleted, ActionFailed, ActionProgress, ActionStarted, Actor, Condition,
+    GoalChanged, GoapAction, GoapAppExt, GoapPlugin, PlanCompleted, PlanCreated, PlanFailed,
+    PlanFailureReason,
+};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+struct HasAxeCondition;
+
+#[derive(Condition)]
+struct HasWoodCondition;
+
+#[derive(Component, Clone)]
+struct GetAxeAction;
+
+impl GoapAction for GetAxeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Complete
+    }
+}
+
+#[derive(Component, Clone)]
+struct ChopTreeAction {
+    succeeds: bool,
+}
+
+impl GoapAction for ChopTreeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        if self.succeeds {
+            ActionProgress::Complete
+        } else {
+            ActionProgress::Failure
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Received {
+    PlanCreated(usize, i32),
+    PlanFailed(PlanFailureReason),
+    PlanCompleted,
+    ActionStarted(Entity),
+    ActionCompleted(Entity),
+    ActionFailed(Entity),
+    GoalChanged,
+}
+
+#[derive(Resource, Default)]
+struct ReceivedEvents(Vec<Received>);
+
+#[derive(SystemParam)]
+struct Readers<'w, 's> {
+    plan_created: EventReader<'w, 's, PlanCreated>,
+    plan_failed: EventReader<'w, 's, PlanFailed>,
+    plan_completed: EventReader<'w, 's, PlanCompleted>,
+    action_started: EventReader<'w, 's, ActionStarted>,
+    action_completed: EventReader<'w, 's, ActionCompleted>,
+    action_failed: EventReader<'w, 's, ActionFailed>,
+    goal_changed: EventReader<'w, 's, GoalChanged>,
+}
+
+fn receive_events_system(mut readers: Readers, mut received: ResMut<ReceivedEvents>) {
+    for ev in readers.plan_created.iter() {
+        received
+            .0
+            .push(Received::PlanCreated(ev.steps.len(), ev.cost));
+    }
+    for ev in readers.plan_failed.iter() {
+        received.0.push(Received::PlanFailed(ev.reason.clone()));
+    }
+    for _ in readers.plan_completed.iter() {
+        received.0.push(Received::PlanCompleted);
+    }
+    for ev in readers.action_started.iter() {
+        received.0.push(Received::ActionStarted(ev.action_entity));
+    }
+    for ev in readers.action_completed.iter() {
+        received.0.push(Received::ActionCompleted(ev.action_entity));
+    }
+    for ev in readers.action_failed.iter() {
+        received.0.push(Received::ActionFailed(ev.action_entity));
+    }
+    for _ in readers.goal_changed.iter() {
+        received.0.push(Received::GoalChanged);
+    }
+}
+
+fn create_app(chop_tree_succeeds: bool) -> App {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default())
+        .init_resource::<ReceivedEvents>()
+        .add_goap_action::<GetAxeAction>()
+        .add_goap_action::<ChopTreeAction>()
+        .add_system_to_stage(CoreStage::First, receive_events_system);
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HasAxeCondition, false)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_goal(HasWoodCondition, true)
+        .with_action(
+            Action::build(GetAxeAction)
+                .with_precondition(HasAxeCondition, false)
+                .with_postcondition(HasAxeCondition, true),
+        )
+        .with_action(
+            Action::build(ChopTreeAction {
+                succeeds: chop_tree_succeeds,
+            })
+            .with_precondition(HasAxeCondition, true)
+            .with_postcondition(HasWoodCondition, true),
+        );
+
+    app.world.spawn(lumberjack);
+    app
+}
+
+fn action_entity<T: Component>(app: &mut App) -> Entity {
+    app.world
+        .query_filtered::<Entity, With<T>>()
+        .single(&app.world)
+}
+
+fn received(app: &App) -> Vec<Received> {
+    app.world.resource::<ReceivedEvents>().0.clone()
+}
+
+#[test]
+fn events_are_sent_for_completed_plan() {
+    let mut app = create_app(true);
+
+    for _ in 0..8 {
+        app.update();
+    }
+
+    let get_axe = action_entity::<GetAxeAction>(&mut app);
+    let chop_tree = action_entity::<ChopTreeAction>(&mut app);
+
+    assert_eq!(
+        received(&app),
+        vec![
+            Received::PlanCreated(2, 2),
+            Received::ActionStarted(get_axe),
+            Received::ActionStarted(chop_tree),
+            Received::ActionCompleted(get_axe),
+            Received::PlanCompleted,
+            Received::ActionCompleted(chop_tree),
+        ]
+    );
+}
+
+#[test]
+fn events_are_sent_for_failed_action() {
+    let mut app = create_app(false);
+
+    for _ in 0..8 {
+        app.update();
+    }
+
+    let chop_tree = action_entity::<ChopTreeAction>(&mut app);
+    let received = received(&app);
+
+    assert!(received.contains(&Received::ActionFailed(chop_tree)));
+    assert!(
+        received.contains(&Received::PlanFailed(PlanFailureReason::ActionFailed(
+            chop_tree
+        )))
+    );
+    assert!(!received.contains(&Received::PlanCompleted));
+}
+
+#[test]
+fn goal_changed_event_is_sent() {
+    let mut app = create_app(true);
+
+    for _ in 0..8 {
+        app.update();
+    }
+
+    app.world
+        .query::<&mut Actor>()
+        .single_mut(&mut app.world)
+        .remove_goal(HasWoodCondition);
+
+    app.update();
+    app.update();
+
+    assert_eq!(
+        received(&app)
+            .iter()
+            .filter(|r| **r == Received::GoalChanged)
+            .count(),
+        1
+    );
+}
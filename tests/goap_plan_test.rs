@@ -0,0 +1,70 @@
+#![cfg(feature = "domain")]
+
+use std::process::{Command, Output};
+
+fn goap_plan(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_goap-plan"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn prints_plan() {
+    let output = goap_plan(&["tests/assets/lumberjack.goap.ron"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("lumberjack: plan with cost 3 (2 actions)"));
+    assert!(stdout.contains("  1. get_axe (cost 1)\n  2. chop_tree (cost 2)"));
+    assert!(stdout.contains("  expanded 2 nodes in"));
+    assert!(stdout.contains("planned for 1 actors in"));
+}
+
+#[test]
+fn fails_without_plan() {
+    let output = goap_plan(&["tests/assets/unsolvable.goap.json"]);
+    let stdout = stdout(&output);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout.contains("lumberjack: no plan found"));
+    assert!(stdout.contains("run with --explain"));
+}
+
+#[test]
+fn explains_failed_plan() {
+    let output = goap_plan(&[
+        "tests/assets/unsolvable.goap.json",
+        "--actor",
+        "lumberjack",
+        "--explain",
+    ]);
+    let stdout = stdout(&output);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout.contains("  goal HasWood = true cannot be reached"));
+    assert!(stdout
+        .contains("  action chop_tree can never be chosen, as HasAxe = true cannot be reached"));
+    assert!(stdout.contains("  initial state: HasAxe = false, HasWood = false"));
+}
+
+#[test]
+fn rejects_invalid_arguments() {
+    assert_eq!(goap_plan(&[]).status.code(), Some(2));
+    assert_eq!(
+        goap_plan(&["tests/assets/lumberjack.goap.ron", "--actor", "miner"])
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(
+        goap_plan(&["tests/assets/lumberjack.goap.ron", "--max-nodes", "many"])
+            .status
+            .code(),
+        Some(2)
+    );
+}
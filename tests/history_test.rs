@@ -0,0 +1,252 @@
+#![cfg(feature = "bevy")]
+
+use bevy::{
+    ecs::system::SystemParamItem,
+    prelude::{App, Component, Entity, With},
+};
+use bevy_goap::{
+    Action, ActionProgress, Actor, ActorState, Condition, GoapAction, GoapAppExt, GoapPlugin,
+    PlanFailureReason, PlanHistory, PlanOutcome, PlannerSettings,
+};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+#[condition(name = "HasAxe")]
+struct HasAxeCondition;
+
+#[derive(Condition)]
+#[condition(name = "HasWood")]
+struct HasWoodCondition;
+
+#[derive(Condition)]
+#[condition(name = "TreeAvailable")]
+struct TreeAvailableCondition;
+
+/// Completes after `ticks` updates of executing.
+#[derive(Component, Clone)]
+struct GetAxeAction {
+    ticks: u32,
+}
+
+impl GoapAction for GetAxeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        self.ticks = self.ticks.saturating_sub(1);
+
+        if self.ticks == 0 {
+            ActionProgress::Complete
+        } else {
+            ActionProgress::Running
+        }
+    }
+}
+
+#[derive(Component, Clone)]
+struct ChopTreeAction {
+    progress: ActionProgress,
+}
+
+impl GoapAction for ChopTreeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        self.progress
+    }
+}
+
+fn create_app(progress: ActionProgress, history_capacity: usize) -> App {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default())
+        .add_goap_action::<GetAxeAction>()
+        .add_goap_action::<ChopTreeAction>();
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HasAxeCondition, false)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_initial_condition(TreeAvailableCondition, true)
+        .with_goal(HasWoodCondition, true)
+        .with_action(
+            Action::build(GetAxeAction { ticks: 1 })
+                .with_precondition(HasAxeCondition, false)
+                .with_postcondition(HasAxeCondition, true),
+        )
+        .with_action(
+            Action::build(ChopTreeAction { progress })
+                .with_precondition(HasAxeCondition, true)
+                .with_precondition(TreeAvailableCondition, true)
+                .with_postcondition(HasWoodCondition, true),
+        );
+
+    app.world
+        .spawn(lumberjack)
+        .insert(PlanHistory::new(history_capacity));
+    app
+}
+
+fn history(app: &mut App) -> PlanHistory {
+    app.world
+        .query_filtered::<&PlanHistory, With<Lumberjack>>()
+        .single(&app.world)
+        .clone()
+}
+
+fn chop_tree(app: &mut App) -> Entity {
+    app.world
+        .query_filtered::<Entity, With<ChopTreeAction>>()
+        .single(&app.world)
+}
+
+fn request_plan(app: &mut App) {
+    *app.world
+        .query_filtered::<&mut ActorState, With<Lumberjack>>()
+        .single_mut(&mut app.world) = ActorState::RequiresPlan;
+}
+
+#[test]
+fn completed_plan_is_recorded() {
+    let mut app = create_app(ActionProgress::Complete, 4);
+
+    for _ in 0..8 {
+        app.update();
+    }
+
+    let history = history(&mut app);
+    let record = history.latest().unwrap();
+
+    assert_eq!(history.records().len(), 1);
+    assert_eq!(record.goal, vec![("HasWood".to_string(), true)]);
+    assert_eq!(
+        record.start_state,
+        vec![
+            ("HasAxe".to_string(), false),
+            ("HasWood".to_string(), false),
+            ("TreeAvailable".to_string(), true)
+        ]
+    );
+    assert_eq!(
+        record.actions,
+        vec![
+            std::any::type_name::<GetAxeAction>().to_string(),
+            std::any::type_name::<ChopTreeAction>().to_string()
+        ]
+    );
+    assert_eq!(record.cost, 2);
+    assert_eq!(record.outcome, PlanOutcome::Completed);
+}
+
+#[test]
+fn failed_plan_is_recorded() {
+    let mut app = create_app(ActionProgress::Failure, 4);
+
+    for _ in 0..8 {
+        app.update();
+    }
+
+    let history = history(&mut app);
+
+    assert_eq!(
+        history.records().next().unwrap().outcome,
+        PlanOutcome::Failed {
+            step: 1,
+            action: std::any::type_name::<ChopTreeAction>().to_string(),
+            reason: PlanFailureReason::ActionFailed(chop_tree(&mut app)),
+        }
+    );
+}
+
+#[test]
+fn interrupted_plans_are_recorded_up_to_capacity() {
+    let mut app = create_app(ActionProgress::Running, 2);
+
+    for _ in 0..3 {
+        for _ in 0..6 {
+            app.update();
+        }
+
+        request_plan(&mut app);
+    }
+
+    app.update();
+    app.update();
+
+    let history = history(&mut app);
+    let outcomes = history
+        .records()
+        .map(|record| record.outcome.clone())
+        .collect::<Vec<_>>();
+
+    // Once the axe was taken, later plans only need to chop the tree.
+    assert_eq!(
+        outcomes,
+        vec![
+            PlanOutcome::Interrupted { step: 0 },
+            PlanOutcome::InProgress
+        ]
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn plan_history_is_serializable() {
+    let mut app = create_app(ActionProgress::Complete, 4);
+
+    for _ in 0..8 {
+        app.update();
+    }
+
+    let history = history(&mut app);
+    let json = serde_json::to_string(&history).unwrap();
+    let deserialized: PlanHistory = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(
+        deserialized.records().collect::<Vec<_>>(),
+        history.records().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn invalidated_plan_is_recorded() {
+    let mut app = create_app(ActionProgress::Running, 4);
+    app.insert_resource(PlannerSettings {
+        replan_on_change: true,
+        ..Default::default()
+    });
+
+    // Builds the Actions of the lumberjack.
+    app.update();
+    app.world
+        .query::<&mut GetAxeAction>()
+        .single_mut(&mut app.world)
+        .ticks = 100;
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    // The tree is gone while the lumberjack is still getting the axe.
+    app.world
+        .query_filtered::<&mut Actor, With<Lumberjack>>()
+        .single_mut(&mut app.world)
+        .update_current_state(TreeAvailableCondition, false);
+    app.update();
+
+    assert_eq!(
+        history(&mut app).records().next().unwrap().outcome,
+        PlanOutcome::Failed {
+            step: 0,
+            action: std::any::type_name::<GetAxeAction>().to_string(),
+            reason: PlanFailureReason::PlanInvalidated,
+        }
+    );
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "bevy")]
+
 use std::{any::TypeId, collections::HashMap};
 
 use bevy::prelude::{App, Component, Entity, Query, SystemSet, With};
@@ -106,7 +108,7 @@ fn two_actions_cheapest_path_fixture() -> ActorTestCase {
 #[case(two_actions_cheapest_path_fixture())]
 fn integration_test(#[case] actor_test_case: ActorTestCase) {
     let mut app = App::new();
-    app.add_plugin(GoapPlugin);
+    app.add_plugin(GoapPlugin::default());
     app.add_system_set_to_stage(
         GoapStage::Actions,
         SystemSet::new()
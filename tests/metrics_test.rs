@@ -0,0 +1,114 @@
+#![cfg(feature = "bevy")]
+
+use bevy::prelude::{App, Component, Query, With};
+use bevy_goap::{
+    Action, ActionState, Actor, ActorState, Condition, EvaluationResult, GoapMetrics, GoapPlugin,
+    GoapStage, PlannerSettings,
+};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+struct HasAxeCondition;
+
+#[derive(Condition)]
+struct HasWoodCondition;
+
+#[derive(Component, Clone)]
+struct GetAxeAction;
+
+#[derive(Component, Clone)]
+struct ChopTreeAction;
+
+fn evaluate_action_system(mut query: Query<&mut ActionState>) {
+    for mut action_state in query.iter_mut() {
+        match *action_state {
+            ActionState::Evaluate => {
+                *action_state = ActionState::EvaluationComplete(EvaluationResult::Success);
+            }
+            ActionState::NotInPlan(_) => *action_state = ActionState::Idle,
+            _ => {}
+        }
+    }
+}
+
+fn create_app(planner: PlannerSettings) -> App {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin {
+        planner,
+        ..Default::default()
+    })
+    .add_system_to_stage(GoapStage::Actions, evaluate_action_system);
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HasAxeCondition, false)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_goal(HasWoodCondition, true)
+        .with_action(
+            Action::build(GetAxeAction)
+                .with_precondition(HasAxeCondition, false)
+                .with_postcondition(HasAxeCondition, true),
+        )
+        .with_action(
+            Action::build(ChopTreeAction)
+                .with_precondition(HasAxeCondition, true)
+                .with_postcondition(HasWoodCondition, true),
+        );
+
+    app.world.spawn(lumberjack);
+    app
+}
+
+fn request_plan(app: &mut App) {
+    *app.world
+        .query_filtered::<&mut ActorState, With<Lumberjack>>()
+        .single_mut(&mut app.world) = ActorState::RequiresPlan;
+}
+
+#[test]
+fn metrics_are_recorded_for_search() {
+    let mut app = create_app(PlannerSettings::default());
+
+    app.update();
+    app.update();
+
+    let metrics = app.world.resource::<GoapMetrics>();
+
+    assert_eq!(metrics.searches, 1);
+    assert_eq!(metrics.plans_last_frame, 1);
+    assert_eq!(metrics.queue_length, 0);
+    assert_eq!(metrics.requeues_pending_evaluation, 1);
+    assert!(metrics.max_nodes_expanded > 0);
+    assert_eq!(
+        metrics.avg_nodes_expanded(),
+        metrics.max_nodes_expanded as f64
+    );
+    assert_eq!(metrics.cache_hit_rate(), None);
+}
+
+#[test]
+fn cached_plan_is_reused() {
+    let mut app = create_app(PlannerSettings {
+        plan_cache_size: Some(8),
+        ..Default::default()
+    });
+
+    app.update();
+    app.update();
+
+    request_plan(&mut app);
+
+    app.update();
+    app.update();
+
+    let metrics = app.world.resource::<GoapMetrics>();
+
+    assert_eq!(metrics.searches, 1);
+    assert_eq!(metrics.cache_misses, 1);
+    assert_eq!(metrics.cache_hits, 1);
+    assert_eq!(metrics.cache_hit_rate(), Some(0.5));
+
+    let actor = app.world.query::<&Actor>().single(&app.world);
+    assert_eq!(actor.planned_actions().len(), 2);
+}
@@ -0,0 +1,171 @@
+#![cfg(feature = "bevy")]
+
+use bevy::prelude::{App, Component, Entity, Query, With};
+use bevy_goap::{
+    Action, ActionState, Actor, ActorState, Condition, EvaluationResult, ExcludedAction,
+    GoapPlugin, GoapStage, NoPlanExplanation, UnreachablePreconditions,
+};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+#[condition(name = "HasAxe")]
+struct HasAxeCondition;
+
+#[derive(Condition)]
+#[condition(name = "HasSaw")]
+struct HasSawCondition;
+
+#[derive(Condition)]
+#[condition(name = "HasWood")]
+struct HasWoodCondition;
+
+#[derive(Condition)]
+#[condition(name = "HasFire")]
+struct HasFireCondition;
+
+#[derive(Component, Clone)]
+struct GetAxeAction;
+
+#[derive(Component, Clone)]
+struct SawTreeAction;
+
+#[derive(Component, Clone)]
+struct CollectWoodAction;
+
+fn evaluate_action_system(mut query: Query<(&mut ActionState, Option<&CollectWoodAction>)>) {
+    for (mut action_state, collect_wood) in query.iter_mut() {
+        match *action_state {
+            ActionState::Evaluate => {
+                *action_state = ActionState::EvaluationComplete(if collect_wood.is_some() {
+                    EvaluationResult::Failure
+                } else {
+                    EvaluationResult::Success
+                });
+            }
+            ActionState::NotInPlan(_) => *action_state = ActionState::Idle,
+            _ => {}
+        }
+    }
+}
+
+fn create_app(goal_is_fire: bool) -> App {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default())
+        .add_system_to_stage(GoapStage::Actions, evaluate_action_system);
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HasAxeCondition, false)
+        .with_initial_condition(HasSawCondition, false)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_action(
+            Action::build(GetAxeAction)
+                .with_precondition(HasAxeCondition, false)
+                .with_postcondition(HasAxeCondition, true),
+        )
+        .with_action(
+            Action::build(SawTreeAction)
+                .with_precondition(HasSawCondition, true)
+                .with_postcondition(HasWoodCondition, true),
+        )
+        .with_action(Action::build(CollectWoodAction).with_postcondition(HasWoodCondition, true));
+
+    let lumberjack = if goal_is_fire {
+        lumberjack.with_goal(HasFireCondition, true)
+    } else {
+        lumberjack.with_goal(HasWoodCondition, true)
+    };
+
+    app.world.spawn(lumberjack);
+    app
+}
+
+fn action_entity<T: Component>(app: &mut App) -> Entity {
+    app.world
+        .query_filtered::<Entity, With<T>>()
+        .single(&app.world)
+}
+
+fn explanation(app: &mut App) -> Option<NoPlanExplanation> {
+    app.world
+        .query_filtered::<Option<&NoPlanExplanation>, With<Lumberjack>>()
+        .single(&app.world)
+        .cloned()
+}
+
+#[test]
+fn no_plan_is_explained() {
+    let mut app = create_app(false);
+
+    app.update();
+    app.update();
+    app.update();
+
+    assert_eq!(
+        *app.world.query::<&ActorState>().single(&app.world),
+        ActorState::NoPlanAvailable
+    );
+
+    let collect_wood = action_entity::<CollectWoodAction>(&mut app);
+    let saw_tree = action_entity::<SawTreeAction>(&mut app);
+
+    assert_eq!(
+        explanation(&mut app),
+        Some(NoPlanExplanation {
+            unproducible_goals: vec![],
+            unreachable_goals: vec![("HasWood", true)],
+            excluded_actions: vec![ExcludedAction {
+                action_entity: collect_wood,
+                name: std::any::type_name::<CollectWoodAction>(),
+                result: EvaluationResult::Failure,
+            }],
+            unreachable_preconditions: vec![UnreachablePreconditions {
+                action_entity: saw_tree,
+                name: std::any::type_name::<SawTreeAction>(),
+                preconditions: vec![("HasSaw", true)],
+            }],
+        })
+    );
+}
+
+#[test]
+fn unproducible_goal_is_explained() {
+    let mut app = create_app(true);
+
+    app.update();
+    app.update();
+    app.update();
+
+    let explanation = explanation(&mut app).unwrap();
+
+    assert_eq!(explanation.unproducible_goals, vec![("HasFire", true)]);
+    assert_eq!(explanation.unreachable_goals, vec![("HasFire", true)]);
+}
+
+#[test]
+fn explanation_is_removed_when_plan_is_created() {
+    let mut app = create_app(false);
+
+    app.update();
+    app.update();
+    app.update();
+
+    assert!(explanation(&mut app).is_some());
+
+    let mut actor = app.world.query::<&mut Actor>().single_mut(&mut app.world);
+    actor.update_current_state(HasSawCondition, true);
+    *app.world
+        .query::<&mut ActorState>()
+        .single_mut(&mut app.world) = ActorState::RequiresPlan;
+
+    app.update();
+    app.update();
+    app.update();
+
+    assert_eq!(
+        *app.world.query::<&ActorState>().single(&app.world),
+        ActorState::ExecutingPlan
+    );
+    assert!(explanation(&mut app).is_none());
+}
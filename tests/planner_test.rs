@@ -0,0 +1,262 @@
+use bevy_goap::planner::{
+    explain, plan, regress, repair, search, Action, Domain, PlannerStrategy, SearchOptions, State,
+};
+
+fn lumberjack() -> Domain<&'static str, &'static str> {
+    Domain::new()
+        .with_action(
+            Action::new("get_axe")
+                .with_precondition("has_axe", false)
+                .with_effect("has_axe", true),
+        )
+        .with_action(
+            Action::new("chop_tree")
+                .with_precondition("has_axe", true)
+                .with_effect("has_wood", true)
+                .with_cost(2),
+        )
+        .with_action(
+            Action::new("buy_wood")
+                .with_effect("has_wood", true)
+                .with_cost(5),
+        )
+}
+
+fn start() -> State<&'static str> {
+    State::new().with("has_axe", false).with("has_wood", false)
+}
+
+#[test]
+fn finds_cheapest_plan() {
+    let goal = State::new().with("has_wood", true);
+    let plan = lumberjack().plan(&start(), &goal).unwrap();
+
+    assert_eq!(plan.actions, vec!["get_axe", "chop_tree"]);
+    assert_eq!(plan.cost, 3);
+    assert_eq!(
+        plan.end_state,
+        State::new().with("has_axe", true).with("has_wood", true)
+    );
+}
+
+#[test]
+fn no_plan_for_unreachable_or_satisfied_goal() {
+    let domain = lumberjack();
+
+    assert_eq!(
+        plan(
+            &start(),
+            &State::new().with("has_food", true),
+            &domain.actions
+        ),
+        None
+    );
+    assert_eq!(
+        plan(
+            &start(),
+            &State::new().with("has_wood", false),
+            &domain.actions
+        ),
+        None
+    );
+}
+
+#[test]
+fn dijkstra_finds_plan_cheaper_than_heuristic() {
+    // The heuristic overestimates when an Action satisfies several goal conditions for less than
+    // their count.
+    let actions = vec![
+        Action::new("one")
+            .with_effect("a", true)
+            .with_effect("b", true)
+            .with_cost(5),
+        Action::new("two")
+            .with_precondition("c", true)
+            .with_effect("a", true)
+            .with_effect("b", true)
+            .with_cost(1),
+        Action::new("three").with_effect("c", true).with_cost(1),
+    ];
+    let goal = State::new().with("a", true).with("b", true);
+
+    let dijkstra = search(
+        &State::new(),
+        &goal,
+        &actions,
+        &SearchOptions {
+            strategy: PlannerStrategy::Dijkstra,
+            ..Default::default()
+        },
+        |_, _| {},
+    );
+
+    assert_eq!(dijkstra.plan.unwrap().actions, vec!["three", "two"]);
+}
+
+#[test]
+fn search_respects_expansion_budget() {
+    let goal = State::new().with("has_wood", true);
+    let mut expanded = vec![];
+
+    let result = search(
+        &start(),
+        &goal,
+        &lumberjack().actions,
+        &SearchOptions {
+            max_expanded_nodes: Some(1),
+            ..Default::default()
+        },
+        |node, _| expanded.push(node.action),
+    );
+
+    // Only the start node is expanded, so the more expensive Action generated from it is chosen.
+    assert_eq!(expanded, vec![None]);
+    assert_eq!(result.plan.unwrap().actions, vec!["buy_wood"]);
+    assert_eq!(result.path.len(), 2);
+}
+
+#[test]
+fn explain_reports_unreachable_conditions() {
+    let actions = vec![
+        Action::new("chop_tree")
+            .with_precondition("has_axe", true)
+            .with_effect("has_wood", true),
+        Action::new("sell_wood")
+            .with_precondition("has_wood", true)
+            .with_effect("has_gold", true),
+    ];
+    let goal = State::new().with("has_gold", true).with("has_food", true);
+
+    let explanation = explain(&start(), &goal, &actions);
+
+    assert_eq!(explanation.unproducible_goals, vec![("has_food", true)]);
+    assert_eq!(
+        explanation.unreachable_goals,
+        vec![("has_food", true), ("has_gold", true)]
+    );
+    assert_eq!(
+        explanation.unreachable_preconditions,
+        vec![
+            ("chop_tree", vec![("has_axe", true)]),
+            ("sell_wood", vec![("has_wood", true)]),
+        ]
+    );
+}
+
+#[test]
+fn explain_is_empty_for_reachable_goal() {
+    let goal = State::new().with("has_wood", true);
+
+    assert!(explain(&start(), &goal, &lumberjack().actions).is_empty());
+}
+
+#[test]
+fn repair_bridges_to_remaining_steps() {
+    let domain = lumberjack();
+    let goal = State::new().with("has_wood", true);
+
+    // The axe was lost after it was picked up, before the tree was chopped.
+    let result = repair(
+        &start(),
+        &goal,
+        &["chop_tree"],
+        &domain.actions,
+        &SearchOptions::default(),
+    );
+    let plan = result.plan.unwrap();
+
+    assert_eq!(plan.actions, vec!["get_axe", "chop_tree"]);
+    assert_eq!(plan.cost, 3);
+    assert!(plan.end_state.satisfies(&goal));
+    assert_eq!(result.path.len(), 3);
+}
+
+#[test]
+fn repair_keeps_shortest_executable_suffix() {
+    let domain = lumberjack();
+    let goal = State::new().with("has_wood", true);
+
+    let result = repair(
+        &start().with("has_axe", true),
+        &goal,
+        &["get_axe", "chop_tree"],
+        &domain.actions,
+        &SearchOptions::default(),
+    );
+
+    assert_eq!(result.plan.unwrap().actions, vec!["chop_tree"]);
+    assert_eq!(result.expanded_nodes, 0);
+}
+
+#[test]
+fn repair_minimizes_bridge_and_suffix_cost() {
+    let domain = Domain::new()
+        .with_action(
+            Action::new("walk")
+                .with_precondition("has_boots", true)
+                .with_effect("at_forest", true),
+        )
+        .with_action(
+            Action::new("chop_tree")
+                .with_precondition("at_forest", true)
+                .with_effect("has_wood", true),
+        )
+        .with_action(
+            Action::new("buy_boots")
+                .with_effect("has_boots", true)
+                .with_cost(10),
+        )
+        .with_action(
+            Action::new("drive")
+                .with_effect("at_forest", true)
+                .with_cost(2),
+        );
+    let start = State::new()
+        .with("has_boots", false)
+        .with("at_forest", false)
+        .with("has_wood", false);
+
+    // Bridging to the longest suffix would require buying boots.
+    let result = repair(
+        &start,
+        &State::new().with("has_wood", true),
+        &["walk", "chop_tree"],
+        &domain.actions,
+        &SearchOptions::default(),
+    );
+    let plan = result.plan.unwrap();
+
+    assert_eq!(plan.actions, vec!["drive", "chop_tree"]);
+    assert_eq!(plan.cost, 3);
+}
+
+#[test]
+fn repair_fails_without_remaining_actions() {
+    let mut domain = lumberjack();
+    domain.actions.retain(|action| action.id != "chop_tree");
+
+    let result = repair(
+        &start(),
+        &State::new().with("has_wood", true),
+        &["chop_tree"],
+        &domain.actions,
+        &SearchOptions::default(),
+    );
+
+    assert!(result.plan.is_none());
+}
+
+#[test]
+fn regress_rejects_actions_undoing_the_goal() {
+    let burn_wood = Action::new("burn_wood").with_effect("has_wood", false);
+    let chop_tree = Action::new("chop_tree")
+        .with_precondition("has_axe", true)
+        .with_effect("has_wood", true);
+    let goal = State::new().with("has_wood", true);
+
+    assert_eq!(regress(&goal, &[&burn_wood]), None);
+    assert_eq!(
+        regress(&goal, &[&burn_wood, &chop_tree]),
+        Some(State::new().with("has_axe", true))
+    );
+}
@@ -0,0 +1,135 @@
+#![cfg(feature = "bevy")]
+
+use bevy::{
+    log::LogPlugin,
+    prelude::{App, Component, Query, With},
+};
+use bevy_goap::{
+    Action, ActionState, Actor, ActorState, Condition, EvaluationResult, GoapLogLevel,
+    GoapLogSettings, GoapPlugin, GoapStage, PlannerSettings,
+};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+struct HasAxeCondition;
+
+#[derive(Condition)]
+struct HasWoodCondition;
+
+#[derive(Component, Clone)]
+struct GetAxeAction;
+
+#[derive(Component, Clone)]
+struct ChopTreeAction;
+
+fn evaluate_action_system(mut query: Query<&mut ActionState>) {
+    for mut action_state in query.iter_mut() {
+        match *action_state {
+            ActionState::Evaluate => {
+                *action_state = ActionState::EvaluationComplete(EvaluationResult::Success);
+            }
+            ActionState::NotInPlan(_) => *action_state = ActionState::Idle,
+            _ => {}
+        }
+    }
+}
+
+fn create_app(plugin: GoapPlugin) -> App {
+    let mut app = App::new();
+    app.add_plugin(plugin)
+        .add_system_to_stage(GoapStage::Actions, evaluate_action_system);
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HasAxeCondition, false)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_goal(HasWoodCondition, true)
+        .with_action(
+            Action::build(GetAxeAction)
+                .with_precondition(HasAxeCondition, false)
+                .with_postcondition(HasAxeCondition, true),
+        )
+        .with_action(
+            Action::build(ChopTreeAction)
+                .with_precondition(HasAxeCondition, true)
+                .with_postcondition(HasWoodCondition, true),
+        );
+
+    app.world.spawn(lumberjack);
+    app
+}
+
+fn actor_state(app: &mut App) -> ActorState {
+    app.world
+        .query::<(&ActorState, With<Lumberjack>)>()
+        .single(&app.world)
+        .0
+        .clone()
+}
+
+#[test]
+fn planner_gives_up_when_node_budget_is_exceeded() {
+    let mut app = create_app(GoapPlugin {
+        planner: PlannerSettings {
+            max_expanded_nodes: Some(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+
+    app.update();
+    app.update();
+
+    assert_eq!(actor_state(&mut app), ActorState::NoPlanAvailable);
+}
+
+#[test]
+fn planner_finds_plan_within_node_budget() {
+    let mut app = create_app(GoapPlugin {
+        planner: PlannerSettings {
+            max_expanded_nodes: Some(3),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+
+    app.update();
+    app.update();
+
+    assert_eq!(actor_state(&mut app), ActorState::ExecutingPlan);
+}
+
+#[test]
+fn actions_are_despawned_with_actor() {
+    let mut app = create_app(GoapPlugin::default());
+
+    app.update();
+    assert_eq!(app.world.query::<&Action>().iter(&app.world).len(), 2);
+
+    let actor_entity = app
+        .world
+        .query_filtered::<bevy::prelude::Entity, With<Lumberjack>>()
+        .single(&app.world);
+    app.world.despawn(actor_entity);
+
+    app.update();
+    assert_eq!(app.world.query::<&Action>().iter(&app.world).len(), 0);
+}
+
+#[test]
+fn plan_is_created_with_verbose_logging() {
+    let mut app = create_app(GoapPlugin {
+        log: GoapLogSettings::all(GoapLogLevel::Verbose),
+        ..Default::default()
+    });
+    app.add_plugin(LogPlugin {
+        filter: "bevy_goap=debug".to_string(),
+        ..Default::default()
+    });
+
+    app.update();
+    app.update();
+
+    assert_eq!(actor_state(&mut app), ActorState::ExecutingPlan);
+}
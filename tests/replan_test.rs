@@ -0,0 +1,271 @@
+#![cfg(feature = "bevy")]
+
+mod common;
+
+use bevy::prelude::{App, Bundle, Component, Entity};
+use bevy_goap::{Action, Actor, ActorState, Condition, GoapMetrics, PlannerSettings};
+use common::{actor_state, planned_labels, update_until_executing, Timed};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+struct HasAxeCondition;
+
+#[derive(Condition)]
+struct TreeAvailableCondition;
+
+#[derive(Condition)]
+struct HasWoodCondition;
+
+#[derive(Condition)]
+struct SunnyCondition;
+
+fn lumberjack() -> impl Bundle {
+    Actor::build(Lumberjack)
+        .with_initial_condition(HasAxeCondition, false)
+        .with_initial_condition(TreeAvailableCondition, true)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_initial_condition(SunnyCondition, false)
+        .with_goal(HasWoodCondition, true)
+        .with_action(
+            Action::build(Timed::new("GetAxe", 100))
+                .with_cost(2)
+                .with_postcondition(HasAxeCondition, true),
+        )
+        .with_action(
+            Action::build(Timed::new("ChopTree", 100))
+                .with_cost(5)
+                .with_precondition(HasAxeCondition, true)
+                .with_precondition(TreeAvailableCondition, true)
+                .with_postcondition(HasWoodCondition, true),
+        )
+}
+
+fn create_app(planner: PlannerSettings) -> (App, Entity) {
+    let mut app = common::create_app(planner);
+
+    let actor_entity = app.world.spawn(lumberjack()).id();
+    update_until_executing(&mut app, actor_entity);
+
+    (app, actor_entity)
+}
+
+fn replan_on_change() -> PlannerSettings {
+    PlannerSettings {
+        replan_on_change: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn changed_condition_that_invalidates_the_plan_triggers_a_replan() {
+    let (mut app, actor_entity) = create_app(replan_on_change());
+
+    app.world
+        .get_mut::<Actor>(actor_entity)
+        .unwrap()
+        .update_current_state(TreeAvailableCondition, false);
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert_eq!(actor_state(&app, actor_entity), ActorState::NoPlanAvailable);
+}
+
+#[test]
+fn changed_condition_that_makes_a_cheaper_plan_available_triggers_a_replan() {
+    let (mut app, actor_entity) = create_app(replan_on_change());
+    assert_eq!(
+        planned_labels(&app, actor_entity),
+        vec!["GetAxe", "ChopTree"]
+    );
+
+    // The plan does not depend on the weather.
+    app.world
+        .get_mut::<Actor>(actor_entity)
+        .unwrap()
+        .update_current_state(SunnyCondition, true);
+    app.update();
+
+    assert_eq!(actor_state(&app, actor_entity), ActorState::ExecutingPlan);
+    assert_eq!(
+        planned_labels(&app, actor_entity),
+        vec!["GetAxe", "ChopTree"]
+    );
+
+    // Someone hands over an axe while the lumberjack is still walking to one.
+    app.world
+        .get_mut::<Actor>(actor_entity)
+        .unwrap()
+        .update_current_state(HasAxeCondition, true);
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert_eq!(actor_state(&app, actor_entity), ActorState::ExecutingPlan);
+    assert_eq!(planned_labels(&app, actor_entity), vec!["ChopTree"]);
+
+    // The first plan, the search for a cheaper plan, and the new plan.
+    assert_eq!(app.world.resource::<GoapMetrics>().searches, 3);
+}
+
+#[test]
+fn checks_for_a_cheaper_plan_count_towards_the_plan_budget() {
+    let (mut app, actor_entity) = create_app(PlannerSettings {
+        replan_on_change: true,
+        max_plans_per_frame: Some(1),
+        ..Default::default()
+    });
+
+    app.world
+        .get_mut::<Actor>(actor_entity)
+        .unwrap()
+        .update_current_state(HasAxeCondition, true);
+    let other_actor_entity = app.world.spawn(lumberjack()).id();
+
+    let searches_before = app.world.resource::<GoapMetrics>().searches;
+    let mut plans = 0;
+
+    for _ in 0..5 {
+        app.update();
+
+        let plans_last_frame = app.world.resource::<GoapMetrics>().plans_last_frame;
+        assert!(plans_last_frame <= 1);
+        plans += plans_last_frame;
+    }
+
+    // The check for a cheaper plan, the new plan, and the plan of the other lumberjack.
+    let metrics = app.world.resource::<GoapMetrics>();
+    assert_eq!(metrics.searches - searches_before, 3);
+    assert_eq!(plans, 3);
+    assert!(metrics.requeues_plan_budget > 0);
+
+    assert_eq!(planned_labels(&app, actor_entity), vec!["ChopTree"]);
+    assert_eq!(
+        planned_labels(&app, other_actor_entity),
+        vec!["GetAxe", "ChopTree"]
+    );
+}
+
+#[test]
+fn changed_conditions_are_ignored_by_default() {
+    let (mut app, actor_entity) = create_app(PlannerSettings::default());
+
+    app.world
+        .get_mut::<Actor>(actor_entity)
+        .unwrap()
+        .update_current_state(TreeAvailableCondition, false);
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert_eq!(actor_state(&app, actor_entity), ActorState::ExecutingPlan);
+}
+
+#[test]
+fn replanning_repairs_the_remaining_plan() {
+    let (mut app, actor_entity) = create_app(PlannerSettings {
+        repair_plans: true,
+        ..Default::default()
+    });
+
+    let mut actor = app.world.get_mut::<Actor>(actor_entity).unwrap();
+    actor.update_current_state(HasAxeCondition, true);
+    *app.world.get_mut::<ActorState>(actor_entity).unwrap() = ActorState::RequiresPlan;
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert_eq!(actor_state(&app, actor_entity), ActorState::ExecutingPlan);
+    assert_eq!(planned_labels(&app, actor_entity), vec!["ChopTree"]);
+
+    let metrics = app.world.resource::<GoapMetrics>();
+    assert_eq!(metrics.plans_repaired, 1);
+    assert_eq!(metrics.failed_repairs, 0);
+}
+
+#[test]
+fn repairing_after_a_failed_step_does_not_keep_the_failed_step() {
+    let mut app = common::create_app(PlannerSettings {
+        repair_plans: true,
+        ..Default::default()
+    });
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HasAxeCondition, false)
+        .with_initial_condition(TreeAvailableCondition, true)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_goal(HasWoodCondition, true)
+        .with_action(
+            Action::build(Timed::failing("GetAxe", 1))
+                .with_cost(2)
+                .with_postcondition(HasAxeCondition, true),
+        )
+        .with_action(
+            Action::build(Timed::new("ChopTree", 100))
+                .with_cost(5)
+                .with_precondition(HasAxeCondition, true)
+                .with_precondition(TreeAvailableCondition, true)
+                .with_postcondition(HasWoodCondition, true),
+        );
+
+    let actor_entity = app.world.spawn(lumberjack).id();
+    update_until_executing(&mut app, actor_entity);
+
+    for _ in 0..5 {
+        app.update();
+
+        if actor_state(&app, actor_entity) == ActorState::FailedDuringPlan {
+            break;
+        }
+    }
+
+    assert_eq!(
+        actor_state(&app, actor_entity),
+        ActorState::FailedDuringPlan
+    );
+
+    let nodes_expanded = app.world.resource::<GoapMetrics>().total_nodes_expanded;
+    *app.world.get_mut::<ActorState>(actor_entity).unwrap() = ActorState::RequiresPlan;
+    update_until_executing(&mut app, actor_entity);
+
+    assert_eq!(
+        planned_labels(&app, actor_entity),
+        vec!["GetAxe", "ChopTree"]
+    );
+
+    // Only ChopTree is kept, so the repair searches for a new way to get an axe.
+    let metrics = app.world.resource::<GoapMetrics>();
+    assert_eq!(metrics.plans_repaired, 1);
+    assert!(metrics.total_nodes_expanded > nodes_expanded);
+}
+
+#[test]
+fn replanning_for_a_cheaper_plan_does_not_repair() {
+    let (mut app, actor_entity) = create_app(PlannerSettings {
+        replan_on_change: true,
+        repair_plans: true,
+        ..Default::default()
+    });
+
+    app.world
+        .get_mut::<Actor>(actor_entity)
+        .unwrap()
+        .update_current_state(HasAxeCondition, true);
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert_eq!(actor_state(&app, actor_entity), ActorState::ExecutingPlan);
+    assert_eq!(planned_labels(&app, actor_entity), vec!["ChopTree"]);
+
+    let metrics = app.world.resource::<GoapMetrics>();
+    assert_eq!(metrics.plans_repaired, 0);
+    assert_eq!(metrics.failed_repairs, 0);
+}
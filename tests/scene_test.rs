@@ -0,0 +1,191 @@
+#![cfg(feature = "bevy")]
+
+use bevy::{
+    ecs::{entity::EntityMap, system::SystemParamItem},
+    prelude::{
+        App, AppTypeRegistry, Component, Entity, FromWorld, Reflect, ReflectComponent, With,
+    },
+    scene::DynamicScene,
+};
+use bevy_goap::{
+    Action, ActionProgress, Actor, ActorState, Condition, GoapAction, GoapAppExt, GoapPlugin,
+};
+
+#[derive(Component, Reflect, Clone, Default)]
+#[reflect(Component)]
+struct Lumberjack;
+
+#[derive(Condition)]
+#[condition(name = "HasAxe")]
+struct HasAxeCondition;
+
+#[derive(Condition)]
+#[condition(name = "HasWood")]
+struct HasWoodCondition;
+
+#[derive(Component, Reflect, Clone, Default)]
+#[reflect(Component)]
+struct GetAxeAction;
+
+impl GoapAction for GetAxeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Complete
+    }
+}
+
+#[derive(Component, Reflect, Clone, Default)]
+#[reflect(Component)]
+struct ChopTreeAction;
+
+impl GoapAction for ChopTreeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Complete
+    }
+}
+
+fn create_app() -> App {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default())
+        .add_goap_action::<GetAxeAction>()
+        .add_goap_action::<ChopTreeAction>()
+        .register_type::<Lumberjack>()
+        .register_type::<GetAxeAction>()
+        .register_type::<ChopTreeAction>();
+    app
+}
+
+/// Saves a world with a lumberjack that has taken the axe, and is about to chop the tree.
+fn save_mid_plan() -> (DynamicScene, AppTypeRegistry) {
+    let mut app = create_app();
+
+    app.world.spawn(
+        Actor::build(Lumberjack)
+            .with_initial_condition(HasAxeCondition, false)
+            .with_initial_condition(HasWoodCondition, false)
+            .with_goal(HasWoodCondition, true)
+            .with_action(
+                Action::build(GetAxeAction)
+                    .with_precondition(HasAxeCondition, false)
+                    .with_postcondition(HasAxeCondition, true),
+            )
+            .with_action(
+                Action::build(ChopTreeAction)
+                    .with_precondition(HasAxeCondition, true)
+                    .with_postcondition(HasWoodCondition, true),
+            ),
+    );
+
+    for _ in 0..4 {
+        app.update();
+    }
+
+    let actor = app.world.query::<&Actor>().single(&app.world);
+    assert_eq!(actor.current_step(), Some(1));
+
+    let type_registry = app.world.resource::<AppTypeRegistry>().clone();
+    (
+        DynamicScene::from_world(&app.world, &type_registry),
+        type_registry,
+    )
+}
+
+/// Loads the scene into a new app, after spawning some other entities, so that the entities of the
+/// lumberjack differ between apps.
+fn load_and_complete(scene: &DynamicScene) {
+    let mut app = create_app();
+
+    for _ in 0..5 {
+        app.world.spawn_empty();
+    }
+
+    app.update();
+
+    let mut entity_map = EntityMap::default();
+    scene
+        .write_to_world(&mut app.world, &mut entity_map)
+        .unwrap();
+
+    let actor_entity = app
+        .world
+        .query_filtered::<Entity, With<Lumberjack>>()
+        .single(&app.world);
+    let chop_tree = app
+        .world
+        .query_filtered::<Entity, With<ChopTreeAction>>()
+        .single(&app.world);
+
+    let actor = app.world.get::<Actor>(actor_entity).unwrap();
+    assert_eq!(actor.current_step(), Some(1));
+    assert_eq!(actor.current_action(), Some(chop_tree));
+    assert_eq!(
+        app.world.get::<Action>(chop_tree).unwrap().actor_entity,
+        actor_entity
+    );
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert_eq!(
+        *app.world.get::<ActorState>(actor_entity).unwrap(),
+        ActorState::CompletedPlan
+    );
+    assert!(app
+        .world
+        .get::<Actor>(actor_entity)
+        .unwrap()
+        .current_state()
+        .any(|condition| condition == ("HasWood", true)));
+}
+
+#[test]
+fn placeholder_actions_are_ignored() {
+    let mut app = create_app();
+
+    let action = Action::from_world(&mut app.world);
+    assert!(action.is_placeholder());
+    let action_entity = app.world.spawn((action, GetAxeAction)).id();
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert!(app.world.get::<Action>(action_entity).is_some());
+}
+
+#[test]
+fn actor_resumes_from_scene() {
+    let (scene, _) = save_mid_plan();
+    load_and_complete(&scene);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn actor_resumes_from_serialized_scene() {
+    use bevy::scene::serde::SceneDeserializer;
+    use serde::de::DeserializeSeed;
+
+    let (scene, type_registry) = save_mid_plan();
+    let serialized = scene.serialize_ron(&type_registry).unwrap();
+
+    let mut deserializer = ron::Deserializer::from_str(&serialized).unwrap();
+    let scene = SceneDeserializer {
+        type_registry: &type_registry.read(),
+    }
+    .deserialize(&mut deserializer)
+    .unwrap();
+
+    load_and_complete(&scene);
+}
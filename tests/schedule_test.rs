@@ -0,0 +1,123 @@
+#![cfg(feature = "bevy")]
+
+use bevy::prelude::{App, Component, CoreStage, Query, StageLabel, State, With};
+use bevy_goap::{
+    run_in_state, Action, ActionState, Actor, ActorState, Condition, EvaluationResult, GoapAppExt,
+    GoapPlugin, GoapSchedule, GoapScheduleExt, GoapStage,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum GameState {
+    Running,
+    Paused,
+}
+
+#[derive(StageLabel)]
+struct ManualGoapStage;
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+struct HasWoodCondition;
+
+#[derive(Component, Clone)]
+struct CollectWoodAction;
+
+fn collect_wood_action_system(mut query: Query<&mut ActionState, With<CollectWoodAction>>) {
+    for mut action_state in query.iter_mut() {
+        match *action_state {
+            ActionState::Evaluate => {
+                *action_state = ActionState::EvaluationComplete(EvaluationResult::Success);
+            }
+            ActionState::Started => *action_state = ActionState::Executing,
+            ActionState::Executing => *action_state = ActionState::Complete,
+            _ => {}
+        }
+    }
+}
+
+fn actor_state(app: &mut App) -> ActorState {
+    app.world
+        .query::<(&ActorState, With<Lumberjack>)>()
+        .single(&app.world)
+        .0
+        .clone()
+}
+
+fn spawn_lumberjack(app: &mut App) {
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_goal(HasWoodCondition, true)
+        .with_action(Action::build(CollectWoodAction).with_postcondition(HasWoodCondition, true));
+
+    app.world.spawn(lumberjack);
+}
+
+#[test]
+fn nested_schedule_is_paused_by_state() {
+    let mut app = App::new();
+    app.add_state(GameState::Paused)
+        .add_plugin(GoapPlugin {
+            schedule: GoapSchedule::Nested {
+                after: CoreStage::Update.as_label(),
+            },
+            ..Default::default()
+        })
+        .set_goap_run_criteria(run_in_state(GameState::Running))
+        .add_goap_system(GoapStage::Actions, collect_wood_action_system);
+
+    spawn_lumberjack(&mut app);
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    // The actor has not even been built while the game is paused.
+    assert_eq!(app.world.query::<&Actor>().iter(&app.world).len(), 0);
+
+    app.world
+        .resource_mut::<State<GameState>>()
+        .set(GameState::Running)
+        .unwrap();
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert_eq!(actor_state(&mut app), ActorState::CompletedPlan);
+}
+
+#[test]
+fn manual_schedule_runs_systems_added_to_it() {
+    let plugin = GoapPlugin {
+        schedule: GoapSchedule::Manual,
+        ..Default::default()
+    };
+
+    let mut schedule = plugin.create_schedule();
+    schedule.add_goap_system(GoapStage::Actions, collect_wood_action_system);
+
+    let mut app = App::new();
+    app.add_plugin(plugin)
+        .add_stage_after(CoreStage::Update, ManualGoapStage, schedule);
+
+    spawn_lumberjack(&mut app);
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert_eq!(actor_state(&mut app), ActorState::CompletedPlan);
+}
+
+#[test]
+#[should_panic(expected = "GoapScheduleExt")]
+fn adding_systems_to_the_app_with_a_manual_schedule_panics() {
+    App::new()
+        .add_plugin(GoapPlugin {
+            schedule: GoapSchedule::Manual,
+            ..Default::default()
+        })
+        .add_goap_system(GoapStage::Actions, collect_wood_action_system);
+}
@@ -0,0 +1,205 @@
+#![cfg(feature = "bevy")]
+
+mod common;
+
+use bevy::{
+    ecs::system::{
+        lifetimeless::{Read, SQuery, SRes},
+        SystemParamItem,
+    },
+    prelude::{App, Component, Entity, Resource},
+};
+use bevy_goap::{
+    Action, Actor, ActorState, Condition, GoapAppExt, PlannerSettings, Sensor, SensorUpdate,
+};
+use common::{actor_state, update_until_executing, Timed};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Component)]
+struct Inventory {
+    axe: bool,
+}
+
+#[derive(Resource)]
+struct Forest {
+    trees: u32,
+}
+
+#[derive(Condition)]
+struct HasAxeCondition;
+
+impl Sensor for HasAxeCondition {
+    type Param = SQuery<Read<Inventory>>;
+
+    fn sense(actor_entity: Entity, inventories: &mut SystemParamItem<Self::Param>) -> Option<bool> {
+        inventories
+            .get(actor_entity)
+            .ok()
+            .map(|inventory| inventory.axe)
+    }
+}
+
+#[derive(Condition)]
+struct TreeAvailableCondition;
+
+impl Sensor for TreeAvailableCondition {
+    type Param = SRes<Forest>;
+
+    const UPDATE: SensorUpdate = SensorUpdate::EveryUpdate;
+
+    fn sense(_actor_entity: Entity, forest: &mut SystemParamItem<Self::Param>) -> Option<bool> {
+        Some(forest.trees > 0)
+    }
+}
+
+#[derive(Condition)]
+struct ChoppedTreeCondition;
+
+#[derive(Condition)]
+struct HoldsAxeCondition;
+
+impl Sensor for HoldsAxeCondition {
+    type Param = SQuery<Read<Inventory>>;
+
+    const UPDATE: SensorUpdate = SensorUpdate::EveryUpdate;
+
+    fn sense(actor_entity: Entity, inventories: &mut SystemParamItem<Self::Param>) -> Option<bool> {
+        inventories
+            .get(actor_entity)
+            .ok()
+            .map(|inventory| inventory.axe)
+    }
+}
+
+fn create_app(axe: bool, get_axe_ticks: u32) -> (App, Entity) {
+    let mut app = common::create_app(PlannerSettings::default());
+    app.insert_resource(Forest { trees: 1 })
+        .add_goap_sensor::<HasAxeCondition>()
+        .add_goap_sensor::<TreeAvailableCondition>();
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HasAxeCondition, false)
+        .with_initial_condition(TreeAvailableCondition, false)
+        .with_initial_condition(ChoppedTreeCondition, false)
+        .with_goal(ChoppedTreeCondition, true)
+        .with_action(
+            Action::build(Timed::new("GetAxe", get_axe_ticks))
+                .with_cost(5)
+                .with_postcondition(HasAxeCondition, true),
+        )
+        .with_action(
+            Action::build(Timed::new("ChopTree", 1))
+                .with_precondition(HasAxeCondition, true)
+                .with_precondition(TreeAvailableCondition, true)
+                .with_postcondition(ChoppedTreeCondition, true),
+        );
+
+    let actor_entity = app.world.spawn((lumberjack, Inventory { axe })).id();
+    (app, actor_entity)
+}
+
+fn has_axe(app: &App, actor_entity: Entity) -> bool {
+    app.world
+        .get::<Actor>(actor_entity)
+        .unwrap()
+        .current_state()
+        .any(|(name, value)| name.ends_with("HasAxeCondition") && value)
+}
+
+#[test]
+fn sensed_conditions_are_used_for_planning() {
+    let (mut app, actor_entity) = create_app(true, 1);
+    update_until_executing(&mut app, actor_entity);
+
+    let actor = app.world.get::<Actor>(actor_entity).unwrap();
+    assert_eq!(actor.planned_actions().len(), 1);
+    assert!(actor
+        .current_state()
+        .any(|(name, value)| name.ends_with("HasAxeCondition") && value));
+}
+
+#[test]
+fn sensed_change_that_invalidates_the_plan_triggers_a_replan() {
+    let (mut app, actor_entity) = create_app(false, 100);
+    update_until_executing(&mut app, actor_entity);
+    assert_eq!(
+        common::planned_labels(&app, actor_entity),
+        vec!["GetAxe", "ChopTree"]
+    );
+
+    // Taking the axe away is not sensed during the plan.
+    app.world.get_mut::<Inventory>(actor_entity).unwrap().axe = false;
+    app.update();
+    app.update();
+
+    assert_eq!(actor_state(&app, actor_entity), ActorState::ExecutingPlan);
+
+    // The tree is gone while the lumberjack is still getting the axe.
+    app.world.resource_mut::<Forest>().trees = 0;
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert_eq!(actor_state(&app, actor_entity), ActorState::NoPlanAvailable);
+}
+
+#[test]
+fn sensors_before_planning_run_once_another_sensor_invalidates_the_plan() {
+    let (mut app, actor_entity) = create_app(false, 100);
+    update_until_executing(&mut app, actor_entity);
+
+    // Picking up an axe is not sensed during the plan...
+    app.world.get_mut::<Inventory>(actor_entity).unwrap().axe = true;
+    app.update();
+    assert!(!has_axe(&app, actor_entity));
+
+    // ...but it is in the update in which the tree is gone, invalidating the plan.
+    app.world.resource_mut::<Forest>().trees = 0;
+    app.update();
+
+    assert_eq!(actor_state(&app, actor_entity), ActorState::AwaitingPlan);
+    assert!(has_axe(&app, actor_entity));
+}
+
+#[test]
+fn sensed_effect_of_a_started_action_keeps_the_plan() {
+    let mut app = common::create_app(PlannerSettings::default());
+    app.add_goap_sensor::<HoldsAxeCondition>();
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HoldsAxeCondition, false)
+        .with_initial_condition(ChoppedTreeCondition, false)
+        .with_goal(ChoppedTreeCondition, true)
+        .with_action(
+            Action::build(Timed::new("GetAxe", 3))
+                .with_precondition(HoldsAxeCondition, false)
+                .with_postcondition(HoldsAxeCondition, true),
+        )
+        .with_action(
+            Action::build(Timed::new("ChopTree", 1))
+                .with_precondition(HoldsAxeCondition, true)
+                .with_postcondition(ChoppedTreeCondition, true),
+        );
+
+    let actor_entity = app.world.spawn((lumberjack, Inventory { axe: false })).id();
+    update_until_executing(&mut app, actor_entity);
+
+    // The axe is picked up before getting it completes.
+    app.world.get_mut::<Inventory>(actor_entity).unwrap().axe = true;
+    app.update();
+
+    assert_eq!(actor_state(&app, actor_entity), ActorState::ExecutingPlan);
+    assert_eq!(
+        common::planned_labels(&app, actor_entity),
+        vec!["GetAxe", "ChopTree"]
+    );
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert_eq!(actor_state(&app, actor_entity), ActorState::CompletedPlan);
+}
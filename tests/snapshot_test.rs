@@ -0,0 +1,240 @@
+#![cfg(feature = "bevy")]
+
+use bevy::{
+    ecs::system::SystemParamItem,
+    prelude::{App, Component, Entity, With},
+};
+use bevy_goap::{
+    Action, ActionProgress, Actor, ActorSnapshot, ActorState, Condition, GoapAction, GoapAppExt,
+    GoapPlugin, SnapshotError,
+};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+#[condition(name = "HasAxe")]
+struct HasAxeCondition;
+
+#[derive(Condition)]
+#[condition(name = "HasWood")]
+struct HasWoodCondition;
+
+#[derive(Component, Clone)]
+struct GetAxeAction;
+
+impl GoapAction for GetAxeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Complete
+    }
+}
+
+#[derive(Component, Clone)]
+struct ChopTreeAction;
+
+impl GoapAction for ChopTreeAction {
+    type Param = ();
+
+    fn tick(
+        &mut self,
+        _action: &Action,
+        _param: &mut SystemParamItem<Self::Param>,
+    ) -> ActionProgress {
+        ActionProgress::Complete
+    }
+}
+
+/// Creates an app with a lumberjack, optionally spawning some other entities first, so that the
+/// entities of the lumberjack differ between apps.
+fn create_app(padding_entities: usize) -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default())
+        .add_goap_action::<GetAxeAction>()
+        .add_goap_action::<ChopTreeAction>();
+
+    for _ in 0..padding_entities {
+        app.world.spawn_empty();
+    }
+
+    let lumberjack = Actor::build(Lumberjack)
+        .with_initial_condition(HasAxeCondition, false)
+        .with_initial_condition(HasWoodCondition, false)
+        .with_goal(HasWoodCondition, true)
+        .with_action(
+            Action::build(GetAxeAction)
+                .with_precondition(HasAxeCondition, false)
+                .with_postcondition(HasAxeCondition, true),
+        )
+        .with_action(
+            Action::build(ChopTreeAction)
+                .with_precondition(HasAxeCondition, true)
+                .with_postcondition(HasWoodCondition, true),
+        );
+
+    let actor_entity = app.world.spawn(lumberjack).id();
+    (app, actor_entity)
+}
+
+fn actor_state(app: &mut App) -> ActorState {
+    app.world
+        .query_filtered::<&ActorState, With<Lumberjack>>()
+        .single(&app.world)
+        .clone()
+}
+
+fn captured_mid_plan() -> ActorSnapshot {
+    let (mut app, actor_entity) = create_app(0);
+
+    // Get the axe, and start chopping the tree.
+    for _ in 0..5 {
+        app.update();
+    }
+
+    let actor = app.world.get::<Actor>(actor_entity).unwrap();
+    assert_eq!(actor.current_step(), Some(1));
+
+    ActorSnapshot::capture(&app.world, actor_entity).unwrap()
+}
+
+fn restore_and_complete(snapshot: &ActorSnapshot) {
+    let (mut app, actor_entity) = create_app(5);
+    app.update();
+
+    snapshot.restore(&mut app.world, actor_entity).unwrap();
+
+    let chop_tree = app
+        .world
+        .query_filtered::<Entity, With<ChopTreeAction>>()
+        .single(&app.world);
+    let actor = app.world.get::<Actor>(actor_entity).unwrap();
+
+    assert_ne!(snapshot.actor_entity, actor_entity);
+    assert_eq!(actor.current_step(), Some(1));
+    assert_eq!(actor.current_action(), Some(chop_tree));
+    assert_eq!(
+        app.world.get::<Action>(chop_tree).unwrap().actor_entity,
+        actor_entity
+    );
+
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert_eq!(actor_state(&mut app), ActorState::CompletedPlan);
+}
+
+#[test]
+fn actor_is_restored_mid_plan() {
+    restore_and_complete(&captured_mid_plan());
+}
+
+#[test]
+fn snapshot_requires_same_actions() {
+    let snapshot = captured_mid_plan();
+
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default());
+    let actor_entity = app
+        .world
+        .spawn(
+            Actor::build(Lumberjack)
+                .with_action(Action::build(GetAxeAction).with_postcondition(HasAxeCondition, true)),
+        )
+        .id();
+    app.update();
+
+    assert!(matches!(
+        snapshot.restore(&mut app.world, actor_entity),
+        Err(SnapshotError::MismatchedActions)
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn snapshot_is_restored_from_json() {
+    let json = serde_json::to_string(&captured_mid_plan()).unwrap();
+
+    assert!(json.contains("\"HasAxe\":true"));
+
+    restore_and_complete(&serde_json::from_str(&json).unwrap());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn unregistered_condition_is_not_deserialized() {
+    let json = serde_json::to_string(&captured_mid_plan())
+        .unwrap()
+        .replace("HasWood", "HasFire");
+
+    let error = serde_json::from_str::<ActorSnapshot>(&json).unwrap_err();
+
+    assert!(error.to_string().contains("unknown Condition \"HasFire\""));
+}
+
+#[cfg(feature = "serde")]
+#[derive(Condition)]
+#[condition(name = "HasSaw")]
+struct HasSawCondition;
+
+#[cfg(feature = "serde")]
+#[derive(Condition)]
+#[condition(name = "HasSaw")]
+struct HasOtherSawCondition;
+
+#[cfg(feature = "serde")]
+#[test]
+fn conditions_with_same_name_are_rejected() {
+    let mut app = App::new();
+    app.register_goap_condition::<HasSawCondition>().unwrap();
+
+    let error = app
+        .register_goap_condition::<HasOtherSawCondition>()
+        .unwrap_err();
+    assert_eq!(error.name, "HasSaw");
+    assert!(error.condition.ends_with("HasOtherSawCondition"));
+
+    // Registering the same Condition again is not a conflict.
+    assert!(app.register_goap_condition::<HasSawCondition>().is_ok());
+}
+
+#[cfg(feature = "serde")]
+#[derive(Condition)]
+#[condition(name = "HasHammer")]
+struct HasHammerCondition;
+
+#[cfg(feature = "serde")]
+#[derive(Condition)]
+#[condition(name = "HasHammer")]
+struct HasOtherHammerCondition;
+
+#[cfg(feature = "serde")]
+#[test]
+fn conditions_with_same_name_are_not_serialized() {
+    let mut app = App::new();
+    app.add_plugin(GoapPlugin::default());
+
+    let actor_entity = app
+        .world
+        .spawn(
+            Actor::build(Lumberjack)
+                .with_initial_condition(HasHammerCondition, true)
+                .with_initial_condition(HasOtherHammerCondition, false),
+        )
+        .id();
+    app.update();
+
+    // Both Conditions can be used, as they are told apart by their type.
+    let actor = app.world.get::<Actor>(actor_entity).unwrap();
+    assert_eq!(actor.current_state().count(), 2);
+
+    let error = serde_json::to_string(actor).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("more than one Condition is named \"HasHammer\""));
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "bevy")]
+
+use bevy::prelude::Component;
+use bevy_goap::{Action, Actor, Condition, DomainIssue};
+
+#[derive(Component, Clone)]
+struct Lumberjack;
+
+#[derive(Condition)]
+#[condition(name = "HasAxe")]
+struct HasAxeCondition;
+
+#[derive(Condition)]
+#[condition(name = "HasSaw")]
+struct HasSawCondition;
+
+#[derive(Condition)]
+#[condition(name = "HasWood")]
+struct HasWoodCondition;
+
+#[derive(Condition)]
+#[condition(name = "HasFire")]
+struct HasFireCondition;
+
+#[derive(Component, Clone)]
+struct GetAxeAction;
+
+#[derive(Component, Clone)]
+struct ChopTreeAction;
+
+#[derive(Component, Clone)]
+struct SawTreeAction;
+
+#[derive(Component, Clone)]
+struct WaitAction;
+
+#[test]
+fn valid_domain_has_no_issues() {
+    let report = Actor::build(Lumberjack)
+        .with_initial_condition(HasAxeCondition, false)
+        .with_goal(HasWoodCondition, true)
+        .with_action(
+            Action::build(GetAxeAction)
+                .with_precondition(HasAxeCondition, false)
+                .with_postcondition(HasAxeCondition, true),
+        )
+        .with_action(
+            Action::build(ChopTreeAction)
+                .with_precondition(HasAxeCondition, true)
+                .with_postcondition(HasWoodCondition, true),
+        )
+        .validate();
+
+    assert!(report.is_valid());
+}
+
+#[test]
+fn invalid_domain_is_reported() {
+    let get_axe = Action::build(GetAxeAction)
+        .with_precondition(HasAxeCondition, false)
+        .with_postcondition(HasAxeCondition, true);
+
+    let report = Actor::build(Lumberjack)
+        .with_initial_condition(HasAxeCondition, false)
+        .with_goal(HasWoodCondition, true)
+        .with_goal(HasFireCondition, true)
+        .with_action(get_axe.clone())
+        .with_action(
+            Action::build(SawTreeAction)
+                .with_precondition(HasSawCondition, true)
+                .with_postcondition(HasWoodCondition, true),
+        )
+        .with_action(get_axe)
+        .with_action(Action::build(WaitAction))
+        .validate();
+
+    assert!(!report.is_valid());
+    assert_eq!(
+        report.issues,
+        vec![
+            DomainIssue::UnproducibleGoal {
+                condition: "HasFire",
+                value: true,
+            },
+            DomainIssue::UnsatisfiablePrecondition {
+                action: 1,
+                name: std::any::type_name::<SawTreeAction>(),
+                condition: "HasSaw",
+                value: true,
+            },
+            DomainIssue::DuplicateAction {
+                action: 2,
+                duplicate_of: 0,
+                name: std::any::type_name::<GetAxeAction>(),
+            },
+            DomainIssue::EmptyPostconditions {
+                action: 3,
+                name: std::any::type_name::<WaitAction>(),
+            },
+        ]
+    );
+}